@@ -3,13 +3,19 @@ use std::iter::FromIterator;
 
 use crate::detail::idents_from_assign_expression;
 use crate::macro_parsing::macro_expression::MacroExpression;
+use crate::macro_parsing::panic_idiom::{IfPanicIdiom, LetAssertIdiom};
 use proc_macro2::{Ident, Span};
 use quote::ToTokens;
 use syn::fold::Fold;
 use syn::parse::{Parse, ParseBuffer};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{fold, Expr, Macro, Path, PathArguments, PathSegment, Token};
+use syn::{
+    Expr, ExprIf, ExprMacro, ExprMethodCall, Macro, MacroDelimiter, Path, PathArguments,
+    PathSegment, Stmt, Token,
+};
+
+mod token_stream;
 
 /// the crate name of the assert2ify crate and not this macro crate itself
 const DEFAULT_ASSERT2IFY_CRATE_NAME: &str = "assert2ify";
@@ -37,6 +43,12 @@ pub struct Assert2Ification {
     /// this will usually be "assert2ify", but the user can tell the macro
     /// that the crate was loaded under a different name
     crate_name: String,
+    /// whether to replace the calls `expression.unwrap()` and `expression.expect(message)`
+    /// on Results and Options, too
+    unwrap: bool,
+    /// whether to replace the idioms that panic unless a condition holds or a value matches a
+    /// pattern, like `if condition { panic!(...) }` or `let pattern = expression else { panic!(...) };`
+    panics: bool,
 }
 
 impl Assert2Ification {
@@ -44,14 +56,24 @@ impl Assert2Ification {
     /// # Arguments
     /// * `configuration`: the configuration to apply. This tells us what to replace the assertions with
     /// * `crate_name`: If Some, this is the name of the crate above in this workspace (assert2ify). It
-    /// could be that the user imported this crate under another name which is why we give the option
-    /// to specify it. If None, we'll just assume that the crate has not been imported under another name
-    fn new<S: Into<String>>(configuration: Style, crate_name: Option<S>) -> Assert2Ification {
+    ///   could be that the user imported this crate under another name which is why we give the option
+    ///   to specify it. If None, we'll just assume that the crate has not been imported under another name
+    /// * `unwrap`: whether to replace calls to `unwrap()` and `expect(message)`, too
+    /// * `panics`: whether to replace the idioms that panic unless a condition holds or a value
+    ///   matches a pattern, too
+    fn new<S: Into<String>>(
+        configuration: Style,
+        crate_name: Option<S>,
+        unwrap: bool,
+        panics: bool,
+    ) -> Assert2Ification {
         Assert2Ification {
             configuration,
             crate_name: crate_name
                 .map(|n| n.into())
                 .unwrap_or_else(|| DEFAULT_ASSERT2IFY_CRATE_NAME.to_string()),
+            unwrap,
+            panics,
         }
     }
 
@@ -59,26 +81,35 @@ impl Assert2Ification {
     /// and gives us a path to the appropriate replacement macro in the assert2ify crate,
     /// depending on the configuration this is either ::assert2ify::__assertify or
     /// ::assert2ify::__checkify
-    fn assert2_macro_path_with_span(&self, span: Span) -> syn::Path {
+    /// # Arguments
+    /// * `span`: the span to give the path
+    /// * `debug_only`: if true, the path points to ::assert2ify::__debug_assertify or
+    ///   ::assert2ify::__debug_checkify instead, which are only checked if debug assertions are enabled
+    fn assert2_macro_path_with_span(&self, span: Span, debug_only: bool) -> syn::Path {
+        let macro_name = match (&self.configuration, debug_only) {
+            (Style::Assertify, false) => "__assertify",
+            (Style::Checkify, false) => "__checkify",
+            (Style::Assertify, true) => "__debug_assertify",
+            (Style::Checkify, true) => "__debug_checkify",
+        };
+        self.macro_path_with_span(span, macro_name)
+    }
+
+    /// A helper function that gives us the path `::assert2ify::macro_name` to the macro with the
+    /// given name in the assert2ify crate, with the given span
+    fn macro_path_with_span(&self, span: Span, macro_name: &str) -> syn::Path {
         let assert2ify = PathSegment {
             ident: Ident::new(self.crate_name.as_str(), span),
             arguments: PathArguments::None,
         };
-
-        let replacement_assertion = match self.configuration {
-            Style::Assertify => PathSegment {
-                ident: Ident::new("__assertify", span),
-                arguments: PathArguments::None,
-            },
-            Style::Checkify => PathSegment {
-                ident: Ident::new("__checkify", span),
-                arguments: PathArguments::None,
-            },
+        let replacement_macro = PathSegment {
+            ident: Ident::new(macro_name, span),
+            arguments: PathArguments::None,
         };
 
         let assert2_segments = Punctuated::<PathSegment, syn::token::Colon2>::from_iter(vec![
             assert2ify,
-            replacement_assertion,
+            replacement_macro,
         ]);
 
         Path {
@@ -86,6 +117,59 @@ impl Assert2Ification {
             segments: assert2_segments,
         }
     }
+
+    /// replace the (already folded) method call by the `__unwrap!` macro of the assert2ify crate
+    /// if it is a call `expression.unwrap()` or `expression.expect(message)`. Any other method
+    /// call is returned unchanged.
+    fn replace_unwrap(&self, method_call: ExprMethodCall) -> Expr {
+        let is_unwrap = method_call.method == "unwrap" && method_call.args.is_empty();
+        let is_expect = method_call.method == "expect" && method_call.args.len() == 1;
+        if method_call.turbofish.is_some() || !(is_unwrap || is_expect) {
+            return Expr::MethodCall(method_call);
+        }
+        let span = method_call.method.span();
+        let receiver = method_call.receiver;
+        let args = method_call.args;
+        let tokens = if is_expect {
+            quote::quote_spanned! {span => #receiver, #args}
+        } else {
+            quote::quote_spanned! {span => #receiver}
+        };
+        Expr::Macro(ExprMacro {
+            attrs: method_call.attrs,
+            mac: Macro {
+                path: self.macro_path_with_span(span, "__unwrap"),
+                bang_token: syn::token::Bang { spans: [span; 1] },
+                delimiter: MacroDelimiter::Paren(syn::token::Paren { span }),
+                tokens,
+            },
+        })
+    }
+
+    /// replace the idiom `if condition { panic!(...) }` by the equivalent assertion on the
+    /// negated condition, see [IfPanicIdiom]
+    /// # Returns
+    /// The statement invoking the replacement macro or None if the expression is not this idiom
+    fn replace_if_panic(&mut self, expr_if: &ExprIf) -> Option<Stmt> {
+        let idiom = IfPanicIdiom::try_from_if(expr_if)?.fold_with(self);
+        let span = idiom.assertion.span;
+        let path = self.assert2_macro_path_with_span(span, false);
+        Some(Stmt::Semi(
+            Expr::Macro(ExprMacro {
+                attrs: Vec::new(),
+                mac: idiom.assert2ify_with(path),
+            }),
+            syn::token::Semi { spans: [span] },
+        ))
+    }
+
+    /// replace the `match` or `if let` expression by a `match` that prints the value like
+    /// `let_assert!` does if it does not match the pattern, see [LetAssertIdiom]
+    fn replace_with_let_assert(&mut self, idiom: LetAssertIdiom) -> Expr {
+        let idiom = idiom.fold_with(self);
+        let path = self.macro_path_with_span(idiom.span, "__let_assert_failure");
+        idiom.into_match_with(path)
+    }
 }
 
 /// Parse this from the arguments given to the attribute like macro
@@ -101,12 +185,19 @@ impl Parse for Assert2Ification {
         // optional argument: check
         // this indicates to use CHECKIFY configuration. Its absence indicates ASSERTIFY
         let mut style: Option<Style> = None;
+        // optional argument: unwrap
+        // this indicates that calls to unwrap() and expect(...) are replaced, too
+        let mut unwrap = false;
+        // optional argument: panics
+        // this indicates that the idioms which panic unless a condition holds or a value matches
+        // a pattern are replaced, too
+        let mut panics = false;
 
         for args in arguments.iter() {
             match args {
                 Expr::Assign(expr_assign) => {
                     // this can only be crate = crate_name
-                    if let Some((lhs, rhs)) = idents_from_assign_expression(&expr_assign) {
+                    if let Some((lhs, rhs)) = idents_from_assign_expression(expr_assign) {
                         if lhs == "crate" {
                             if crate_name.is_none() {
                                 crate_name = Some(rhs.to_string());
@@ -129,6 +220,24 @@ impl Parse for Assert2Ification {
                         ));
                     }
                 }
+                Expr::Path(expr_path) if expr_path.path.is_ident("unwrap") => {
+                    if unwrap {
+                        return Err(syn::Error::new(
+                            expr_path.span(),
+                            "Illegal argument. `unwrap` was already specified",
+                        ));
+                    }
+                    unwrap = true;
+                }
+                Expr::Path(expr_path) if expr_path.path.is_ident("panics") => {
+                    if panics {
+                        return Err(syn::Error::new(
+                            expr_path.span(),
+                            "Illegal argument. `panics` was already specified",
+                        ));
+                    }
+                    panics = true;
+                }
                 Expr::Path(expr_path) => {
                     if expr_path.path.is_ident("check") {
                         if style.is_none() {
@@ -142,7 +251,7 @@ impl Parse for Assert2Ification {
                     } else {
                         return Err(syn::Error::new(
                             expr_path.span(),
-                            "Illegal argument. Did you mean `check`?",
+                            "Illegal argument. Did you mean `check`, `unwrap` or `panics`?",
                         ));
                     }
                 }
@@ -154,27 +263,76 @@ impl Parse for Assert2Ification {
         Ok(Assert2Ification::new(
             style.unwrap_or(Style::Assertify),
             crate_name,
+            unwrap,
+            panics,
         ))
     }
 }
 
 impl Fold for Assert2Ification {
+    /// Fold the syntax tree and, if the `unwrap` argument was given, replace the method calls
+    /// `expression.unwrap()` and `expression.expect(message)` by the `__unwrap!` macro of the super crate.
+    /// The method calls (and any expressions inside them) are folded first, so nested calls like
+    /// `a.unwrap().b().unwrap()` are replaced, too. If the `panics` argument was given, matches and
+    /// `if let` expressions, in which all but one pattern diverge with `panic!` or `unreachable!`,
+    /// are replaced by a `match` that fails like `let_assert!`.
+    /// All other expressions are folded as usual.
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match expr {
+            Expr::MethodCall(method_call) if self.unwrap => {
+                let method_call = self.fold_expr_method_call(method_call);
+                self.replace_unwrap(method_call)
+            }
+            Expr::Match(expr_match) if self.panics => {
+                match LetAssertIdiom::try_from_match(&expr_match) {
+                    Some(idiom) => self.replace_with_let_assert(idiom),
+                    None => syn::fold::fold_expr(self, Expr::Match(expr_match)),
+                }
+            }
+            Expr::If(expr_if) if self.panics => match LetAssertIdiom::try_from_if_let(&expr_if) {
+                Some(idiom) => self.replace_with_let_assert(idiom),
+                None => syn::fold::fold_expr(self, Expr::If(expr_if)),
+            },
+            other => syn::fold::fold_expr(self, other),
+        }
+    }
+
+    /// Fold the statements and, if the `panics` argument was given, replace the idiom
+    /// `if condition { panic!(...) }` by an assertion that the condition is false, with the
+    /// arguments of the panic as the info arguments. We only do this for statements, because the
+    /// check style of assertions must be a statement.
+    /// The statement `let pattern = expression else { panic!(...) };` is replaced by a `let` statement
+    /// that fails like `let_assert!`, too.
+    fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        let replacement = match &stmt {
+            Stmt::Expr(Expr::If(expr_if)) | Stmt::Semi(Expr::If(expr_if), _) if self.panics => {
+                self.replace_if_panic(expr_if)
+            }
+            Stmt::Semi(Expr::Verbatim(tokens), _) if self.panics => {
+                LetAssertIdiom::try_from_let_else(tokens).map(|idiom| {
+                    let idiom = idiom.fold_with(self);
+                    let path = self.macro_path_with_span(idiom.span, "__let_assert_failure");
+                    idiom.into_let_with(path)
+                })
+            }
+            _ => None,
+        };
+        replacement.unwrap_or_else(|| syn::fold::fold_stmt(self, stmt))
+    }
+
     /// Fold the syntax tree and replace standard library assert macros by the assertion macros from the super crate
     /// # Result
     /// The replaced or untouched macros.
     /// # A Note On Nested Macros
-    /// If an assertion macro is encountered, then this assertion is parsed ond no further folding
-    /// on the expression(s) inside the assertion are performed. That means nested asserts won't get
-    /// replaced. If you have them, the chances are high that your code is unreadable anyways.
+    /// If an assertion macro is encountered, then this assertion is parsed and the expression(s)
+    /// inside the assertion as well as the info arguments are folded before the replacement is
+    /// built. That means nested asserts will get replaced, too.
     ///
     /// If the macro is not an assertion, this function looks into the tokens and tries to fold them.
-    /// However, the folding is only performed if the tokens in the macro can be parsed as an expression
-    /// Then the parser is recursively invoked. This will parse nested macros **to a degree**
-    /// Expr covers a lot of things, but it will
-    /// not cover all possible paths, e.g. multiple statements separated by
-    /// a semicolon (unless they are themselves enclosed in a block). So for assert expressions
-    /// that are themselves enclosed in a macro it is not guaranteed that they will be assertifyed
-    /// or checkifyed.
+    /// If the tokens in the macro can be parsed as an expression, the parser is recursively invoked.
+    /// Otherwise (e.g. for multiple statements separated by a semicolon) the raw tokens are searched
+    /// for assertion macros at any depth, which are then replaced in place. All other tokens
+    /// stay unchanged.
     fn fold_macro(&mut self, mac: Macro) -> Macro {
         let macro_parse_result = MacroExpression::try_from(mac.clone());
         // we check whether the macro could be parsed. If not, this indicates a syntax error in the
@@ -186,23 +344,30 @@ impl Fold for Assert2Ification {
 
             match macro_expression {
                 MacroExpression::Assertion(assertion) => {
-                    assertion.assert2ify_with(self.assert2_macro_path_with_span(span))
+                    // replace the assertions nested inside this assertion first
+                    let assertion = assertion.fold_with(self);
+                    let path = self.assert2_macro_path_with_span(span, assertion.debug_only);
+                    assertion.assert2ify_with(path)
                 }
                 MacroExpression::Other(other_macro) => {
                     // this looks into the tokens in the macro and if they are an expression,
                     // the parser is recursively invoked. This will parse nested macros.
-                    // I don't think we need to recursively pass anything other than expressions
-                    // in macros but I might be wrong. Expr covers a lot of things, but it will
-                    // not cover all possible paths, for example multiple statements separated by
-                    // a semicolon (unless they are themselves enclosed in a block)
-                    if let Ok(nested_expr) = syn::parse2::<Expr>(other_macro.tokens.clone()) {
-                        let folded = self.fold_expr(nested_expr);
-                        Macro {
-                            tokens: folded.to_token_stream(),
-                            ..other_macro
-                        }
+                    // Expr covers a lot of things, but it will not cover all possible paths,
+                    // for example multiple statements separated by a semicolon (unless they
+                    // are themselves enclosed in a block). For those we fall back to walking
+                    // the raw tokens and replacing the assertions we find in there.
+                    let tokens = if other_macro.path.is_ident("macro_rules") {
+                        // the assertions inside macro definitions need some special care
+                        self.fold_macro_rules_definition(other_macro.tokens.clone())
+                    } else if let Ok(nested_expr) = syn::parse2::<Expr>(other_macro.tokens.clone())
+                    {
+                        self.fold_expr(nested_expr).to_token_stream()
                     } else {
-                        fold::fold_macro(self, other_macro)
+                        self.fold_token_stream(other_macro.tokens.clone())
+                    };
+                    Macro {
+                        tokens,
+                        ..other_macro
                     }
                 }
             }
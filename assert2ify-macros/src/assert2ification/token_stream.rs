@@ -0,0 +1,256 @@
+use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::fold::Fold;
+use syn::{Macro, MacroDelimiter};
+
+use crate::assert2ification::Assert2Ification;
+use crate::detail::{infer_macro_kind_from_path, METAVARIABLE_PLACEHOLDER_PREFIX};
+
+impl Assert2Ification {
+    /// Fold a raw token stream that could not be parsed as an expression (e.g. the arguments of
+    /// a macro that contain a list of statements) by walking the tokens and replacing
+    /// assertion macros at any depth.
+    /// # Returns
+    /// The token stream with the assertions replaced. All other tokens stay exactly as they were.
+    pub(super) fn fold_token_stream(&mut self, tokens: TokenStream) -> TokenStream {
+        self.fold_token_stream_with(tokens, false)
+    }
+
+    /// Fold the rules of a `macro_rules!` definition. The matchers of the rules are left
+    /// untouched, but the assertions inside the transcribers are replaced. The metavariables
+    /// inside the arguments of the assertions are carried over to the replacement.
+    /// # Arguments
+    /// * `tokens`: the tokens inside the `macro_rules! name {...}` braces
+    pub(super) fn fold_macro_rules_definition(&mut self, tokens: TokenStream) -> TokenStream {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let mut folded = TokenStream::new();
+
+        for (index, token) in tokens.iter().enumerate() {
+            // a transcriber is the group right after the => of a rule
+            let is_transcriber = index >= 2
+                && is_punct(&tokens[index - 1], '>')
+                && matches!(&tokens[index - 2], TokenTree::Punct(punct) if punct.as_char() == '=' && punct.spacing() == Spacing::Joint);
+            match token {
+                TokenTree::Group(group) if is_transcriber => {
+                    let mut transcriber = Group::new(
+                        group.delimiter(),
+                        self.fold_token_stream_with(group.stream(), true),
+                    );
+                    transcriber.set_span(group.span());
+                    folded.extend(Some(TokenTree::Group(transcriber)));
+                }
+                other => folded.extend(Some(other.clone())),
+            }
+        }
+        folded
+    }
+
+    /// the implementation of the token folding
+    /// # Arguments
+    /// * `tokens`: the tokens to fold
+    /// * `in_macro_definition`: true if the tokens are part of a transcriber of a macro definition,
+    ///   which means that the arguments of the assertions may contain metavariables
+    fn fold_token_stream_with(
+        &mut self,
+        tokens: TokenStream,
+        in_macro_definition: bool,
+    ) -> TokenStream {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let mut folded = TokenStream::new();
+        let mut index = 0;
+
+        while index < tokens.len() {
+            // we must never start looking for a macro in the middle of a path, otherwise
+            // we'd mistake e.g. my_crate::assert!(...) for the std lib assert
+            if let Some(path_length) = path_length_at(&tokens, index) {
+                let path_end = index + path_length;
+                if let Some(mac) = macro_invocation_at(&tokens, index, path_end) {
+                    if infer_macro_kind_from_path(&mac.path).is_assertion() {
+                        if !in_macro_definition {
+                            folded.extend(self.fold_macro(mac).into_token_stream());
+                            index = path_end + 2;
+                            continue;
+                        } else if let Some(tokens) = replace_metavariables(mac.tokens.clone()) {
+                            let folded_macro = self.fold_macro(Macro { tokens, ..mac });
+                            folded.extend(restore_metavariables(folded_macro.into_token_stream()));
+                            index = path_end + 2;
+                            continue;
+                        }
+                    }
+                }
+                folded.extend(tokens[index..path_end].iter().cloned());
+                index = path_end;
+            } else {
+                match &tokens[index] {
+                    TokenTree::Group(group) => folded.extend(Some(TokenTree::Group(
+                        self.fold_group(group, in_macro_definition),
+                    ))),
+                    other => folded.extend(Some(other.clone())),
+                }
+                index += 1;
+            }
+        }
+        folded
+    }
+
+    /// fold the tokens inside a group and keep the delimiters and span of the group
+    fn fold_group(&mut self, group: &Group, in_macro_definition: bool) -> Group {
+        let mut folded = Group::new(
+            group.delimiter(),
+            self.fold_token_stream_with(group.stream(), in_macro_definition),
+        );
+        folded.set_span(group.span());
+        folded
+    }
+}
+
+/// Replace the metavariables `$name` (including `$crate`) in the given tokens by placeholder
+/// identifiers, so that the tokens can be parsed.
+/// # Returns
+/// The tokens with the placeholders or None if the tokens contain something that we can't
+/// replace, i.e. a repetition `$(...)*`.
+fn replace_metavariables(tokens: TokenStream) -> Option<TokenStream> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut replaced = TokenStream::new();
+    let mut index = 0;
+
+    while index < tokens.len() {
+        match (&tokens[index], tokens.get(index + 1)) {
+            (TokenTree::Punct(dollar), Some(next)) if dollar.as_char() == '$' => match next {
+                TokenTree::Ident(ident) => {
+                    let placeholder = Ident::new(
+                        &format!("{}{}", METAVARIABLE_PLACEHOLDER_PREFIX, ident),
+                        ident.span(),
+                    );
+                    replaced.extend(Some(TokenTree::Ident(placeholder)));
+                    index += 2;
+                }
+                _ => return None,
+            },
+            (TokenTree::Group(group), _) => {
+                let mut inner =
+                    Group::new(group.delimiter(), replace_metavariables(group.stream())?);
+                inner.set_span(group.span());
+                replaced.extend(Some(TokenTree::Group(inner)));
+                index += 1;
+            }
+            (other, _) => {
+                replaced.extend(Some(other.clone()));
+                index += 1;
+            }
+        }
+    }
+    Some(replaced)
+}
+
+/// The inverse of [replace_metavariables]: turn the placeholder identifiers back into metavariables
+fn restore_metavariables(tokens: TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|token| -> Vec<TokenTree> {
+            match token {
+                TokenTree::Ident(ident) => {
+                    let name = ident.to_string();
+                    if let Some(metavariable) = name.strip_prefix(METAVARIABLE_PLACEHOLDER_PREFIX) {
+                        let mut dollar = Punct::new('$', Spacing::Alone);
+                        dollar.set_span(ident.span());
+                        vec![
+                            TokenTree::Punct(dollar),
+                            TokenTree::Ident(Ident::new(metavariable, ident.span())),
+                        ]
+                    } else {
+                        vec![TokenTree::Ident(ident)]
+                    }
+                }
+                TokenTree::Group(group) => {
+                    let mut restored =
+                        Group::new(group.delimiter(), restore_metavariables(group.stream()));
+                    restored.set_span(group.span());
+                    vec![TokenTree::Group(restored)]
+                }
+                other => vec![other],
+            }
+        })
+        .collect()
+}
+
+/// helper function that returns the number of tokens that make up the path (e.g. `::std::assert`)
+/// starting at the given index. Returns None if there is no path starting at that index.
+/// Identifiers preceded by `$` are not considered paths, because these are
+/// metavariables in macro definitions. The exception is `$crate`, which begins a path
+/// (like `$crate::assert`) that points into the crate of the macro definition.
+fn path_length_at(tokens: &[TokenTree], index: usize) -> Option<usize> {
+    if is_punct(&tokens[index], '$')
+        && matches!(tokens.get(index + 1), Some(TokenTree::Ident(ident)) if ident == "crate")
+    {
+        return segments_length_at(tokens, index + 1).map(|length| length + 1);
+    }
+    if index > 0 && is_punct(&tokens[index - 1], '$') {
+        return None;
+    }
+    segments_length_at(tokens, index)
+}
+
+/// helper function that returns the number of tokens that make up the segments of a path,
+/// like `::std::assert` or `crate::assert`, starting at the given index
+fn segments_length_at(tokens: &[TokenTree], index: usize) -> Option<usize> {
+    let mut length = 0;
+    if is_path_separator_at(tokens, index) {
+        length += 2;
+    }
+    if !matches!(tokens.get(index + length), Some(TokenTree::Ident(_))) {
+        return None;
+    }
+    length += 1;
+
+    while is_path_separator_at(tokens, index + length)
+        && matches!(tokens.get(index + length + 2), Some(TokenTree::Ident(_)))
+    {
+        length += 3;
+    }
+    Some(length)
+}
+
+/// helper function to check whether a `::` starts at the given index
+fn is_path_separator_at(tokens: &[TokenTree], index: usize) -> bool {
+    match (tokens.get(index), tokens.get(index + 1)) {
+        (Some(TokenTree::Punct(first)), Some(second)) => {
+            first.as_char() == ':' && first.spacing() == Spacing::Joint && is_punct(second, ':')
+        }
+        _ => false,
+    }
+}
+
+/// helper function to check whether the token is the given punctuation character
+fn is_punct(token: &TokenTree, character: char) -> bool {
+    matches!(token, TokenTree::Punct(punct) if punct.as_char() == character)
+}
+
+/// If the path between `path_start` and `path_end` is followed by a `!` and a delimited group,
+/// then this is a macro invocation, which is returned. Otherwise returns None.
+fn macro_invocation_at(tokens: &[TokenTree], path_start: usize, path_end: usize) -> Option<Macro> {
+    let bang = match tokens.get(path_end) {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '!' => punct,
+        _ => return None,
+    };
+    let group = match tokens.get(path_end + 1) {
+        Some(TokenTree::Group(group)) => group,
+        _ => return None,
+    };
+    let delimiter = match group.delimiter() {
+        Delimiter::Parenthesis => MacroDelimiter::Paren(syn::token::Paren { span: group.span() }),
+        Delimiter::Brace => MacroDelimiter::Brace(syn::token::Brace { span: group.span() }),
+        Delimiter::Bracket => MacroDelimiter::Bracket(syn::token::Bracket { span: group.span() }),
+        Delimiter::None => return None,
+    };
+    let path = syn::parse2(tokens[path_start..path_end].iter().cloned().collect()).ok()?;
+
+    Some(Macro {
+        path,
+        bang_token: syn::token::Bang {
+            spans: [bang.span()],
+        },
+        delimiter,
+        tokens: group.stream(),
+    })
+}
@@ -2,6 +2,10 @@ use proc_macro2::Span;
 use syn::spanned::Spanned;
 use syn::{Attribute, BinOp, Expr, ExprAssign, ItemFn};
 
+/// The prefix of the identifiers that temporarily stand in for the metavariables (like `$a`)
+/// of a macro definition, so that the arguments of assertions can be parsed as expressions
+pub const METAVARIABLE_PLACEHOLDER_PREFIX: &str = "__assert2ify_metavariable_";
+
 /// enumeration that names all the standard assertions that can
 /// be handled with this crate
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -12,6 +16,12 @@ pub enum StandardLibraryAssertion {
     AssertNe,
     /// the assertion `assert!`
     Assert,
+    /// the assertion `debug_assert_eq!`
+    DebugAssertEq,
+    /// the assertion `debug_assert_ne!`
+    DebugAssertNe,
+    /// the assertion `debug_assert!`
+    DebugAssert,
 }
 
 /// A helper enumeration that helps us identify which kind of macro we
@@ -41,12 +51,28 @@ impl MacroKind {
     }
 
     /// helper function that indicates whether the class of macro
-    /// is a binary assertion (`assert_eq!` or `assert_ne!`)
+    /// is a binary assertion (`assert_eq!` or `assert_ne!` and their debug variants)
     pub fn is_binary_assertion(&self) -> bool {
         match self {
             Self::Assertion(StandardLibraryAssertion::AssertEq) => true,
             Self::Assertion(StandardLibraryAssertion::AssertNe) => true,
             Self::Assertion(StandardLibraryAssertion::Assert) => false,
+            Self::Assertion(StandardLibraryAssertion::DebugAssertEq) => true,
+            Self::Assertion(StandardLibraryAssertion::DebugAssertNe) => true,
+            Self::Assertion(StandardLibraryAssertion::DebugAssert) => false,
+            Self::Other => false,
+        }
+    }
+
+    /// helper function that indicates whether the class of macro
+    /// is a debug assertion (`debug_assert!`, `debug_assert_eq!` or `debug_assert_ne!`),
+    /// which is only checked if debug assertions are enabled
+    pub fn is_debug_assertion(&self) -> bool {
+        match self {
+            Self::Assertion(StandardLibraryAssertion::DebugAssertEq) => true,
+            Self::Assertion(StandardLibraryAssertion::DebugAssertNe) => true,
+            Self::Assertion(StandardLibraryAssertion::DebugAssert) => true,
+            Self::Assertion(_) => false,
             Self::Other => false,
         }
     }
@@ -61,23 +87,27 @@ impl MacroKind {
     /// Otherwise returns None.
     pub fn binary_operator(&self, span: Span) -> Option<BinOp> {
         match self {
-            Self::Assertion(StandardLibraryAssertion::AssertEq) => {
+            Self::Assertion(StandardLibraryAssertion::AssertEq)
+            | Self::Assertion(StandardLibraryAssertion::DebugAssertEq) => {
                 Some(BinOp::Eq(syn::token::EqEq { spans: [span; 2] }))
             }
-            Self::Assertion(StandardLibraryAssertion::AssertNe) => {
+            Self::Assertion(StandardLibraryAssertion::AssertNe)
+            | Self::Assertion(StandardLibraryAssertion::DebugAssertNe) => {
                 Some(BinOp::Ne(syn::token::Ne { spans: [span; 2] }))
             }
-            Self::Assertion(StandardLibraryAssertion::Assert) => None,
+            Self::Assertion(StandardLibraryAssertion::Assert)
+            | Self::Assertion(StandardLibraryAssertion::DebugAssert) => None,
             Self::Other => None,
         }
     }
 }
 
-/// Using the path from the macro infer whether it is `assert_eq!`, `assert_ne!`, `assert!`, `matches!` or
-/// some entirely different macro.
+/// Using the path from the macro infer whether it is `assert_eq!`, `assert_ne!`, `assert!`, one of
+/// their `debug_` variants or some entirely different macro.
 /// # Arguments
-/// * `path` the path in question. If the path begins with ::std or std, the next segment of the
-/// path is checked whether it is one of the assertions in question.
+/// * `path` the path in question. If the path begins with ::std or std (or core), the next
+///   segment of the path is checked whether it is one of the assertions in question. The same goes
+///   for the last segment of fully qualified prelude paths like `std::prelude::v1::assert`.
 /// # Return
 /// The kind of assertion
 /// # Caveat
@@ -92,6 +122,9 @@ pub fn infer_macro_kind_from_path(path: &syn::Path) -> MacroKind {
         let assert_eq = "assert_eq";
         let assert_ne = "assert_ne";
         let assert = "assert";
+        let debug_assert_eq = "debug_assert_eq";
+        let debug_assert_ne = "debug_assert_ne";
+        let debug_assert = "debug_assert";
 
         if ident == assert_eq {
             MacroKind::from(StandardLibraryAssertion::AssertEq)
@@ -99,24 +132,61 @@ pub fn infer_macro_kind_from_path(path: &syn::Path) -> MacroKind {
             MacroKind::from(StandardLibraryAssertion::AssertNe)
         } else if ident == assert {
             MacroKind::from(StandardLibraryAssertion::Assert)
+        } else if ident == debug_assert_eq {
+            MacroKind::from(StandardLibraryAssertion::DebugAssertEq)
+        } else if ident == debug_assert_ne {
+            MacroKind::from(StandardLibraryAssertion::DebugAssertNe)
+        } else if ident == debug_assert {
+            MacroKind::from(StandardLibraryAssertion::DebugAssert)
         } else {
             MacroKind::Other
         }
     }
 
+    // the standard library assertions are also available from core
+    let is_std_crate = |ident: &syn::Ident| ident == "std" || ident == "core";
+    // the prelude modules, as in std::prelude::v1 or core::prelude::rust_2021
+    let is_prelude_version = |ident: &syn::Ident| {
+        ident == "v1"
+            || ident == "rust_2015"
+            || ident == "rust_2018"
+            || ident == "rust_2021"
+            || ident == "rust_2024"
+    };
+
     if segments.len() == 1 {
         macro_kind(&segments[0])
     } else if segments.len() == 2 {
-        if segments[0] == "std" {
+        if is_std_crate(&segments[0]) {
             macro_kind(&segments[1])
         } else {
             MacroKind::Other
         }
+    } else if segments.len() == 4 {
+        if is_std_crate(&segments[0])
+            && segments[1] == "prelude"
+            && is_prelude_version(&segments[2])
+        {
+            macro_kind(&segments[3])
+        } else {
+            MacroKind::Other
+        }
     } else {
         MacroKind::Other
     }
 }
 
+/// whether the path is the macro of the standard library with the given name (like `panic`),
+/// optionally qualified as `std::name!` or `core::name!`
+pub fn is_std_macro(path: &syn::Path, macro_name: &str) -> bool {
+    let segments: Vec<&syn::Ident> = path.segments.iter().map(|s| &s.ident).collect();
+    match segments.as_slice() {
+        [name] => *name == macro_name,
+        [krate, name] => (*krate == "std" || *krate == "core") && *name == macro_name,
+        _ => false,
+    }
+}
+
 /// This function extracts the identifiers (lhs, rhs) out of an assignment operation lhs = rhs.
 /// If the left and right hand side are not identifiers, then this returns None.
 pub fn idents_from_assign_expression(assignment: &ExprAssign) -> Option<(syn::Ident, syn::Ident)> {
@@ -153,7 +223,7 @@ fn ident_from_box_expr(expr: Box<Expr>) -> Option<syn::Ident> {
 /// * Scan the attributes of the function.
 /// * If `#[test]` is among the attributes, do nothing and return the function
 /// * If `#[test]` is not among the attributes, remove all occurrences of `#[should_panic]` and
-/// `#[ignore]` from the attributes and return the modified function
+///   `#[ignore]` from the attributes and return the modified function
 /// # Additional Info
 /// See [this topic](https://users.rust-lang.org/t/proc-macro-attribute-makes-compiler-shout-at-me-when-should-panic-is-involved/59816/9)
 /// in the users.rust-lang.org forum.
@@ -190,12 +260,14 @@ fn is_attribute_name(attr: &Attribute, name: &str) -> bool {
 
 /// helper function to guard the attribute against redefinition of the same attribute
 /// if so, report an error.
-/// Remark: this attribute itself will not be visible in the list of attributes of the function,
-/// which is why this works
+/// Remark: this attribute itself will not be visible in the list of attributes of the function
+/// (or module), which is why this works
 /// this will not work 100% reliably because someone might use this thing under a different name,
 /// but it does guard against accidental duplication
-pub fn check_redefinition_of_assert2ify(func: &ItemFn) -> Result<(), syn::Error> {
-    if let Some(other_assertify_macro) = func.attrs.iter().find(|attr| {
+/// # Arguments
+/// * `attrs`: the attributes of the item that the attribute was applied to
+pub fn check_redefinition_of_assert2ify(attrs: &[Attribute]) -> Result<(), syn::Error> {
+    if let Some(other_assertify_macro) = attrs.iter().find(|attr| {
         attr.path
             .segments
             .last()
@@ -204,7 +276,7 @@ pub fn check_redefinition_of_assert2ify(func: &ItemFn) -> Result<(), syn::Error>
     }) {
         Err(syn::Error::new(
             other_assertify_macro.span(),
-            "Duplicate attribute. This attribute must only be specified once for each function",
+            "Duplicate attribute. This attribute must only be specified once for each function or module",
         ))
     } else {
         Ok(())
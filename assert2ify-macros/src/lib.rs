@@ -1,8 +1,9 @@
 use proc_macro::TokenStream;
 
-use quote::quote;
+use quote::ToTokens;
 use syn::fold::Fold;
-use syn::{parse_macro_input, ItemFn};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, ImplItemMethod, Item, TraitItemMethod};
 
 use crate::detail::{apply_unused_attributes_workaround, check_redefinition_of_assert2ify};
 use assert2ification::Assert2Ification;
@@ -15,16 +16,7 @@ mod macro_parsing;
 pub fn assert2ify(args: TokenStream, input: TokenStream) -> TokenStream {
     // See this example by dtolnay on how to traverse a syntax tree and replace nodes
     // https://github.com/dtolnay/syn/blob/master/examples/trace-var/trace-var/src/lib.rs
-    let func = parse_macro_input!(input as ItemFn);
-
-    // apply a workaround that will suppress clippy and compiler warnings when
-    // should_panic or ignore are encountered in tests. See the doc of the function for more info
-    let func = apply_unused_attributes_workaround(func);
-
-    // guard this macro (to some degree) against having this attribute specified twice
-    if let Err(error) = check_redefinition_of_assert2ify(&func) {
-        return error.into_compile_error().into();
-    }
+    let item = parse_macro_input!(input as Item);
 
     // Parse the list of variables the user wanted to print.
     let mut assert2ification = parse_macro_input!(args as Assert2Ification);
@@ -34,7 +26,86 @@ pub fn assert2ify(args: TokenStream, input: TokenStream) -> TokenStream {
     // that allows us to traverse nodes and replace them with anything else.
     // Fold just allows us to replace the node with a node of the same type,
     // (i.e. macro with macro), which is fine for my use case
-    let output = assert2ification.fold_item_fn(func);
+    let output = match item {
+        Item::Fn(func) => {
+            // apply a workaround that will suppress clippy and compiler warnings when
+            // should_panic or ignore are encountered in tests. See the doc of the function for more info
+            let func = apply_unused_attributes_workaround(func);
+
+            // guard this macro (to some degree) against having this attribute specified twice
+            if let Err(error) = check_redefinition_of_assert2ify(&func.attrs) {
+                return error.into_compile_error().into();
+            }
+            assert2ification.fold_item_fn(func).into_token_stream()
+        }
+        Item::Mod(module) => {
+            // the contents of modules that live in their own file are not visible to us
+            if module.content.is_none() {
+                return syn::Error::new(
+                    module.span(),
+                    "The #[assert2ify] attribute can only be applied to inline modules, i.e. `mod name { ... }`",
+                )
+                .into_compile_error()
+                .into();
+            }
+            if let Err(error) = check_redefinition_of_assert2ify(&module.attrs) {
+                return error.into_compile_error().into();
+            }
+            // folding the module takes care of all functions, nested modules, impl blocks
+            // and closures inside of it
+            assert2ification.fold_item_mod(module).into_token_stream()
+        }
+        Item::Impl(item_impl) => {
+            if let Err(error) = check_redefinition_of_assert2ify(&item_impl.attrs) {
+                return error.into_compile_error().into();
+            }
+            assert2ification
+                .fold_item_impl(item_impl)
+                .into_token_stream()
+        }
+        Item::Trait(item_trait) => {
+            if let Err(error) = check_redefinition_of_assert2ify(&item_trait.attrs) {
+                return error.into_compile_error().into();
+            }
+            assert2ification
+                .fold_item_trait(item_trait)
+                .into_token_stream()
+        }
+        // methods inside impl blocks and traits mostly parse as functions, which
+        // is handled above. The remaining cases (like trait methods without a default
+        // implementation) are given to us as verbatim tokens, so we try parsing them as methods
+        Item::Verbatim(tokens) => {
+            if let Ok(method) = syn::parse2::<ImplItemMethod>(tokens.clone()) {
+                if let Err(error) = check_redefinition_of_assert2ify(&method.attrs) {
+                    return error.into_compile_error().into();
+                }
+                assert2ification
+                    .fold_impl_item_method(method)
+                    .into_token_stream()
+            } else if let Ok(method) = syn::parse2::<TraitItemMethod>(tokens.clone()) {
+                if let Err(error) = check_redefinition_of_assert2ify(&method.attrs) {
+                    return error.into_compile_error().into();
+                }
+                assert2ification
+                    .fold_trait_item_method(method)
+                    .into_token_stream()
+            } else {
+                return unsupported_item_error(tokens.span());
+            }
+        }
+        other => return unsupported_item_error(other.span()),
+    };
+
+    TokenStream::from(output)
+}
 
-    TokenStream::from(quote!(#output))
+/// helper function to generate the compile error for items that the attribute
+/// cannot be applied to
+fn unsupported_item_error(span: proc_macro2::Span) -> TokenStream {
+    syn::Error::new(
+        span,
+        "The #[assert2ify] attribute can only be applied to functions, methods, modules, impl blocks or traits",
+    )
+    .into_compile_error()
+    .into()
 }
@@ -1,30 +1,583 @@
-use syn::Expr;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::fold::Fold;
+use syn::spanned::Spanned;
+use syn::{
+    BinOp, Expr, ExprBinary, ExprLit, ExprMethodCall, ExprParen, ExprUnary, Ident, Lit, UnOp,
+};
+
+use crate::macro_parsing::matches_macro::MatchesMacro;
+
+/// the methods of iterators that check a predicate on the elements
+const ITERATOR_METHODS: [&str; 2] = ["all", "any"];
+
+/// the methods returning a bool that tell us which variant of an Option or Result we have
+const VARIANT_METHODS: [&str; 4] = ["is_ok", "is_err", "is_some", "is_none"];
+
+/// well known methods that return a bool and take exactly one argument, which we
+/// want to print together with the receiver if the assertion fails
+const METHODS_WITH_ARGUMENT: [&str; 4] = ["contains", "starts_with", "ends_with", "is_match"];
 
 /// An intermediate structure which helps parsing assert use cases and variants
 /// from the std lib and can translate them into assert2 assertions.
+#[allow(clippy::enum_variant_names)]
 pub enum Assertion {
     /// The binary assertions `std::assert_eq!` and `std::assert_ne`
     /// Those are transalated into the equivalent assertion of the assert2 crate
     AssertBinary {
-        lhs: Expr,
+        lhs: Box<Expr>,
         operator: syn::BinOp,
-        rhs: Expr,
+        rhs: Box<Expr>,
     },
     /// Catch all for any other kind of `std::assert!` macro
     /// which is not one of the above.
     /// This means any other kind of assertion on one expression,
     /// notably also assertions on binary expressions like `assert!(a==b)`
     /// or `assert!(v.len() < 5)`.
-    AssertUnary { expr: Expr },
+    AssertUnary { expr: Box<Expr> },
+    /// An assertion on `receiver.is_ok()` or `is_err()`, `is_some()`, `is_none()`. If it
+    /// fails, the value of the receiver is printed.
+    AssertVariant { receiver: Box<Expr>, method: Ident },
+    /// An assertion on `receiver.contains(argument)` or `starts_with`, `ends_with`, `is_match`,
+    /// where the argument is not a closure, since those only get their parameter types inferred
+    /// when they are passed to the method directly. If it fails, the values of the receiver and
+    /// the argument are printed.
+    AssertMethodCall {
+        receiver: Box<Expr>,
+        method: Ident,
+        argument: Box<Expr>,
+    },
+    /// An assertion on `iterator.all(predicate)` or `iterator.any(predicate)` with a closure
+    /// as the predicate, where the iterator is not a place. If it fails, the elements that made it
+    /// fail are printed. Receivers that turn out not to be iterators are checked like any other
+    /// boolean expression.
+    AssertIterator {
+        iterator: Box<Expr>,
+        method: Ident,
+        predicate: Box<Expr>,
+    },
+    /// An assertion on `receiver.is_empty()` or its negation `!receiver.is_empty()`.
+    /// If it fails, the contents of the receiver are printed.
+    AssertIsEmpty { receiver: Box<Expr>, negated: bool },
+    /// An assertion on a chain of conjunctions `assert!(a && b && c)`, which is split
+    /// into one assertion per conjunct, so we can tell which one failed.
+    AssertConjunction { conjuncts: Vec<Assertion> },
 }
 
 impl Assertion {
-    /// Convenience constructor for binary assertions
+    /// Convenience constructor for binary assertions. A comparison with a boolean literal like
+    /// `assert_eq!(v.is_empty(), true)` or `assert_ne!(matches!(..), false)` gives the assertion on
+    /// the other side (or its negation) instead, so that its special forms are recognized, too.
+    /// This is only done if the other side is known to be a `bool`, see [is_bool_expression].
     pub fn new_binary(lhs: Expr, operator: syn::BinOp, rhs: Expr) -> Self {
-        Self::AssertBinary { lhs, operator, rhs }
+        let equal = match operator {
+            BinOp::Eq(_) => Some(true),
+            BinOp::Ne(_) => Some(false),
+            _ => None,
+        };
+        match (equal, bool_literal(&lhs), bool_literal(&rhs)) {
+            (Some(equal), _, Some(value)) if is_bool_expression(&lhs) => {
+                Self::new_truth(lhs, value == equal)
+            }
+            (Some(equal), Some(value), _) if is_bool_expression(&rhs) => {
+                Self::new_truth(rhs, value == equal)
+            }
+            _ => Self::AssertBinary {
+                lhs: Box::new(lhs),
+                operator,
+                rhs: Box::new(rhs),
+            },
+        }
+    }
+
+    /// the assertion that the condition is true (if `expected` is true) or false otherwise
+    fn new_truth(expr: Expr, expected: bool) -> Self {
+        if expected {
+            Self::new_assert(expr)
+        } else {
+            Self::new_negated(expr)
+        }
     }
-    /// convenience case for a general assertion case on one argument
+    /// convenience case for a general assertion case on one argument. If the argument
+    /// is a chain of `&&` conjunctions, this gives an assertion on the conjunction instead.
     pub fn new_assert(expr: Expr) -> Self {
-        Self::AssertUnary { expr }
+        let mut conjuncts: Vec<Assertion> = conjuncts_of(expr)
+            .into_iter()
+            .map(Self::new_condition)
+            .collect();
+        if conjuncts.len() > 1 {
+            Self::AssertConjunction { conjuncts }
+        } else {
+            conjuncts.pop().expect("there is always one conjunct")
+        }
+    }
+
+    /// the assertion that the given condition is false, as in the idiom `if condition { panic!() }`.
+    /// The negation is pushed into the condition where that does not change its meaning, so that
+    /// `a != b` becomes an assertion on `a == b`, `!a` becomes an assertion on `a` and a disjunction
+    /// `a || b` becomes an assertion on the conjunction of the negated disjuncts.
+    pub fn new_negated(expr: Expr) -> Self {
+        match expr {
+            Expr::Paren(ExprParen { expr, .. }) => Self::new_negated(*expr),
+            Expr::Unary(ExprUnary {
+                op: UnOp::Not(_),
+                expr,
+                ..
+            }) => match *expr {
+                Expr::Paren(ExprParen { expr, .. }) => Self::new_assert(*expr),
+                expr => Self::new_assert(expr),
+            },
+            Expr::Binary(ExprBinary {
+                left,
+                op: BinOp::Or(_),
+                right,
+                ..
+            }) => {
+                let conjuncts = vec![*left, *right]
+                    .into_iter()
+                    .flat_map(|disjunct| match Self::new_negated(disjunct) {
+                        Self::AssertConjunction { conjuncts } => conjuncts,
+                        other => vec![other],
+                    })
+                    .collect();
+                Self::AssertConjunction { conjuncts }
+            }
+            Expr::Binary(ExprBinary {
+                left, op, right, ..
+            }) if negated_comparison(&op).is_some()
+                && (matches!(op, BinOp::Eq(_) | BinOp::Ne(_))
+                    || length_receiver(&left).is_some()
+                    || length_receiver(&right).is_some()) =>
+            {
+                let operator = negated_comparison(&op).expect("the operator is a comparison");
+                Self::new_binary(*left, operator, *right)
+            }
+            other => {
+                // only simple expressions can be negated without parentheses
+                let needs_parentheses = !matches!(
+                    other,
+                    Expr::Call(_)
+                        | Expr::Field(_)
+                        | Expr::Index(_)
+                        | Expr::Lit(_)
+                        | Expr::Macro(_)
+                        | Expr::MethodCall(_)
+                        | Expr::Path(_)
+                        | Expr::Unary(_)
+                );
+                let span = other.span();
+                let expr = if needs_parentheses {
+                    Expr::Paren(ExprParen {
+                        attrs: Vec::new(),
+                        paren_token: syn::token::Paren { span },
+                        expr: Box::new(other),
+                    })
+                } else {
+                    other
+                };
+                Self::new_condition(Expr::Unary(ExprUnary {
+                    attrs: Vec::new(),
+                    op: UnOp::Not(syn::token::Bang { spans: [span] }),
+                    expr: Box::new(expr),
+                }))
+            }
+        }
+    }
+
+    /// the assertion on a single condition, which recognizes the well known method calls
+    /// that get special treatment
+    fn new_condition(expr: Expr) -> Self {
+        match expr {
+            Expr::MethodCall(ExprMethodCall {
+                receiver,
+                method,
+                turbofish: None,
+                args,
+                ..
+            }) if (args.is_empty() && VARIANT_METHODS.iter().any(|name| method == name))
+                || (args.len() == 1
+                    && METHODS_WITH_ARGUMENT.iter().any(|name| method == name)
+                    && !matches!(args.first(), Some(Expr::Closure(_)))) =>
+            {
+                match args.into_iter().next() {
+                    None => Self::AssertVariant { receiver, method },
+                    Some(argument) => Self::AssertMethodCall {
+                        receiver,
+                        method,
+                        argument: Box::new(argument),
+                    },
+                }
+            }
+            Expr::MethodCall(ExprMethodCall {
+                receiver,
+                method,
+                turbofish: None,
+                args,
+                ..
+            }) if args.len() == 1
+                && matches!(args.first(), Some(Expr::Closure(_)))
+                && ITERATOR_METHODS.iter().any(|name| method == name)
+                && !is_place(&receiver) =>
+            {
+                Self::AssertIterator {
+                    iterator: receiver,
+                    method,
+                    predicate: Box::new(args.into_iter().next().expect("there is one argument")),
+                }
+            }
+            Expr::Binary(ExprBinary {
+                left, op, right, ..
+            }) if (is_comparison(&op)
+                && (length_receiver(&left).is_some() || length_receiver(&right).is_some()))
+                || (is_ordering(&op)
+                    && (abs_difference(&left).is_some() || abs_difference(&right).is_some())) =>
+            {
+                Self::AssertBinary {
+                    lhs: left,
+                    operator: op,
+                    rhs: right,
+                }
+            }
+            Expr::Unary(ExprUnary {
+                op: UnOp::Not(_),
+                expr,
+                ..
+            }) if is_empty_receiver(&expr).is_some() => match *expr {
+                Expr::MethodCall(ExprMethodCall { receiver, .. }) => Self::AssertIsEmpty {
+                    receiver,
+                    negated: true,
+                },
+                _ => unreachable!("the expression is a call to is_empty()"),
+            },
+            Expr::MethodCall(ExprMethodCall {
+                receiver,
+                method,
+                turbofish: None,
+                args,
+                ..
+            }) if args.is_empty() && method == "is_empty" => Self::AssertIsEmpty {
+                receiver,
+                negated: false,
+            },
+            other => Self::AssertUnary {
+                expr: Box::new(other),
+            },
+        }
+    }
+
+    /// apply the given fold to the expression(s) inside the assertion. This is
+    /// how we replace assertions that are nested inside other assertions.
+    pub fn fold_with<F: Fold>(self, folder: &mut F) -> Self {
+        match self {
+            Self::AssertBinary { lhs, operator, rhs } => Self::AssertBinary {
+                lhs: Box::new(folder.fold_expr(*lhs)),
+                operator,
+                rhs: Box::new(folder.fold_expr(*rhs)),
+            },
+            Self::AssertUnary { expr } => Self::AssertUnary {
+                expr: Box::new(folder.fold_expr(*expr)),
+            },
+            Self::AssertVariant { receiver, method } => Self::AssertVariant {
+                receiver: Box::new(folder.fold_expr(*receiver)),
+                method,
+            },
+            Self::AssertMethodCall {
+                receiver,
+                method,
+                argument,
+            } => Self::AssertMethodCall {
+                receiver: Box::new(folder.fold_expr(*receiver)),
+                method,
+                argument: Box::new(folder.fold_expr(*argument)),
+            },
+            Self::AssertIterator {
+                iterator,
+                method,
+                predicate,
+            } => Self::AssertIterator {
+                iterator: Box::new(folder.fold_expr(*iterator)),
+                method,
+                predicate: Box::new(folder.fold_expr(*predicate)),
+            },
+            Self::AssertIsEmpty { receiver, negated } => Self::AssertIsEmpty {
+                receiver: Box::new(folder.fold_expr(*receiver)),
+                negated,
+            },
+            Self::AssertConjunction { conjuncts } => Self::AssertConjunction {
+                conjuncts: conjuncts
+                    .into_iter()
+                    .map(|conjunct| conjunct.fold_with(folder))
+                    .collect(),
+            },
+        }
+    }
+
+    /// The tokens of the condition that is asserted, which are given to the macros of the
+    /// supercrate (without the info arguments). Some conditions are given a special form,
+    /// which those macros understand:
+    /// * `matches!(...)` is prepended with `@bindings(...)`, which lists the variables bound by its
+    ///   pattern. Those are printed if the guard of the pattern fails, but the macros of the supercrate
+    ///   can't figure out which variables those are.
+    /// * `!matches!(...)` becomes `@not_matches(@bindings(...) matches!(...))`. The bindings are printed
+    ///   if the pattern matches, because the value itself might have been moved into them.
+    /// * [Assertion::AssertVariant] becomes `@variant(receiver, method)`
+    /// * [Assertion::AssertMethodCall] becomes `@method(receiver, method, argument)`
+    /// * [Assertion::AssertIterator] becomes `@all(iterator, predicate)` or `@any(iterator, predicate)`
+    /// * [Assertion::AssertIsEmpty] becomes `@is_empty(receiver)` or `@is_empty(!receiver)`
+    /// * [Assertion::AssertBinary] becomes `@len_left(receiver, operator, rhs)` if the left hand side
+    ///   is `receiver.len()` or `@len_right(lhs, operator, receiver)` if the right hand side is.
+    ///   Otherwise, it becomes `@eq(lhs, rhs)` if the operator is `==` and `@compare(lhs, operator, rhs)`
+    ///   for all other operators.
+    /// * [Assertion::AssertUnary] on a comparison `lhs operator rhs` becomes `@compare(lhs, operator, rhs)`,
+    ///   so that the values of both sides are printed if it fails, just like assert2 does.
+    /// * [Assertion::AssertConjunction] becomes `@and (first) (second) ...`
+    pub fn condition_tokens(&self) -> TokenStream {
+        match self {
+            Self::AssertBinary { lhs, operator, rhs } => {
+                if let Some(receiver) = length_receiver(lhs) {
+                    quote! {@len_left(#receiver, #operator, #rhs)}
+                } else if let Some(receiver) = length_receiver(rhs) {
+                    quote! {@len_right(#lhs, #operator, #receiver)}
+                } else if let Some((minuend, subtrahend)) =
+                    abs_difference(lhs).filter(|_| is_ordering(operator))
+                {
+                    quote! {@abs_diff_left(#minuend, #subtrahend, #operator, #rhs)}
+                } else if let Some((minuend, subtrahend)) =
+                    abs_difference(rhs).filter(|_| is_ordering(operator))
+                {
+                    quote! {@abs_diff_right(#lhs, #operator, #minuend, #subtrahend)}
+                } else if matches!(operator, BinOp::Eq(_)) {
+                    quote! {@eq(#lhs, #rhs)}
+                } else {
+                    quote! {@compare(#lhs, #operator, #rhs)}
+                }
+            }
+            Self::AssertUnary { expr } => {
+                if let Some(matches_macro) = matches_macro_of(expr) {
+                    let bindings = matches_macro.bindings();
+                    quote! {@bindings(#(#bindings),*) #matches_macro}
+                } else if let Some(matches_macro) = negated_matches_macro_of(expr) {
+                    let bindings = matches_macro.bindings();
+                    quote! {@not_matches(@bindings(#(#bindings),*) #matches_macro)}
+                } else if let Some((lhs, operator, rhs)) = comparison_of(expr) {
+                    quote! {@compare(#lhs, #operator, #rhs)}
+                } else {
+                    expr.to_token_stream()
+                }
+            }
+            Self::AssertVariant { receiver, method } => quote! {@variant(#receiver, #method)},
+            Self::AssertMethodCall {
+                receiver,
+                method,
+                argument,
+            } => quote! {@method(#receiver, #method, #argument)},
+            Self::AssertIterator {
+                iterator,
+                method,
+                predicate,
+            } => quote! {@#method(#iterator, #predicate)},
+            Self::AssertIsEmpty {
+                receiver,
+                negated: false,
+            } => quote! {@is_empty(#receiver)},
+            Self::AssertIsEmpty {
+                receiver,
+                negated: true,
+            } => quote! {@is_empty(!#receiver)},
+            Self::AssertConjunction { conjuncts } => {
+                let conjuncts = conjuncts.iter().map(Self::condition_tokens);
+                quote! {@and #((#conjuncts))*}
+            }
+        }
+    }
+}
+
+/// the parsed `matches!` macro, if the expression is one
+fn matches_macro_of(expr: &Expr) -> Option<MatchesMacro> {
+    match expr {
+        Expr::Macro(expr_macro) => MatchesMacro::try_from_macro(&expr_macro.mac),
+        _ => None,
+    }
+}
+
+/// the parsed arguments of the negated `matches!` macro (maybe in parentheses), if the expression
+/// is a negation `!matches!(...)`
+fn negated_matches_macro_of(expr: &Expr) -> Option<MatchesMacro> {
+    let mut negated = match expr {
+        Expr::Unary(ExprUnary {
+            op: UnOp::Not(_),
+            expr,
+            ..
+        }) => expr.as_ref(),
+        _ => return None,
+    };
+    while let Expr::Paren(ExprParen { expr, .. }) = negated {
+        negated = expr;
+    }
+    matches_macro_of(negated)
+}
+
+/// split a chain of conjunctions `a && b && c` into its conjuncts `[a, b, c]`, in order
+/// of evaluation. Any other expression is returned as the only conjunct.
+fn conjuncts_of(expr: Expr) -> Vec<Expr> {
+    match expr {
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::And(_),
+            right,
+            ..
+        }) => {
+            let mut conjuncts = conjuncts_of(*left);
+            conjuncts.extend(conjuncts_of(*right));
+            conjuncts
+        }
+        other => vec![other],
+    }
+}
+
+/// the value of the expression, if it is one of the literals `true` or `false`
+fn bool_literal(expr: &Expr) -> Option<bool> {
+    match expr {
+        Expr::Lit(ExprLit {
+            attrs,
+            lit: Lit::Bool(lit_bool),
+        }) if attrs.is_empty() => Some(lit_bool.value),
+        _ => None,
+    }
+}
+
+/// whether the expression is known to be a `bool` just by looking at it. Other types might be
+/// comparable with a `bool`, too, so comparisons of them with `true` mean something else than
+/// assertions on them. Known to be a `bool` are
+/// * comparisons, conjunctions and disjunctions like `a < b` or `a && b`
+/// * the `matches!` macro
+/// * calls of the methods that are already treated specially, like `is_some()`, `is_empty()`
+///   or `contains(x)`
+/// * the negation `!a` and the parentheses `(a)` of any of the above
+fn is_bool_expression(expr: &Expr) -> bool {
+    match expr {
+        Expr::Binary(ExprBinary { op, .. }) => {
+            is_comparison(op) || matches!(op, BinOp::And(_) | BinOp::Or(_))
+        }
+        Expr::Macro(_) => matches_macro_of(expr).is_some(),
+        Expr::MethodCall(ExprMethodCall { method, args, .. }) => {
+            let is_method = |names: &[&str]| names.iter().any(|name| method == name);
+            match args.len() {
+                0 => is_method(&VARIANT_METHODS) || method == "is_empty",
+                1 => is_method(&METHODS_WITH_ARGUMENT) || is_method(&ITERATOR_METHODS),
+                _ => false,
+            }
+        }
+        Expr::Unary(ExprUnary {
+            op: UnOp::Not(_),
+            expr,
+            ..
+        }) => is_bool_expression(expr),
+        Expr::Paren(ExprParen { expr, .. }) => is_bool_expression(expr),
+        _ => false,
+    }
+}
+
+/// whether the operator is a comparison like `==` or `<`
+fn is_comparison(operator: &BinOp) -> bool {
+    matches!(
+        operator,
+        BinOp::Eq(_) | BinOp::Ne(_) | BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_)
+    )
+}
+
+/// the left hand side, operator and right hand side of the expression, if it is a comparison
+fn comparison_of(expr: &Expr) -> Option<(&Expr, &BinOp, &Expr)> {
+    match expr {
+        Expr::Binary(ExprBinary {
+            left, op, right, ..
+        }) if is_comparison(op) => Some((left, op, right)),
+        _ => None,
+    }
+}
+
+/// the comparison operator that gives the opposite result of the given comparison operator
+/// (with the same spans), like `!=` for `==` or `>=` for `<`. Beware that the opposite of an
+/// ordering is only its negation for totally ordered types.
+fn negated_comparison(operator: &BinOp) -> Option<BinOp> {
+    match operator {
+        BinOp::Eq(token) => Some(BinOp::Ne(syn::token::Ne { spans: token.spans })),
+        BinOp::Ne(token) => Some(BinOp::Eq(syn::token::EqEq { spans: token.spans })),
+        BinOp::Lt(token) => Some(BinOp::Ge(syn::token::Ge {
+            spans: [token.spans[0]; 2],
+        })),
+        BinOp::Le(token) => Some(BinOp::Gt(syn::token::Gt {
+            spans: [token.spans[0]],
+        })),
+        BinOp::Gt(token) => Some(BinOp::Le(syn::token::Le {
+            spans: [token.spans[0]; 2],
+        })),
+        BinOp::Ge(token) => Some(BinOp::Lt(syn::token::Lt {
+            spans: [token.spans[0]],
+        })),
+        _ => None,
+    }
+}
+
+/// whether the operator is an ordering like `<` or `>=`
+fn is_ordering(operator: &BinOp) -> bool {
+    matches!(
+        operator,
+        BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_)
+    )
+}
+
+/// the minuend and subtrahend of the expression, if it is an absolute difference
+/// `(minuend - subtrahend).abs()`
+fn abs_difference(expr: &Expr) -> Option<(&Expr, &Expr)> {
+    match method_call_receiver(expr, "abs")? {
+        Expr::Paren(ExprParen { expr, .. }) => match expr.as_ref() {
+            Expr::Binary(ExprBinary {
+                left,
+                op: BinOp::Sub(_),
+                right,
+                ..
+            }) => Some((left, right)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// the receiver of the expression, if it is a call `receiver.len()`
+fn length_receiver(expr: &Expr) -> Option<&Expr> {
+    method_call_receiver(expr, "len")
+}
+
+/// whether the expression is a place like `iterator` or `self.iterator`, which the check on
+/// `all` or `any` must not move. Other receivers, like `values.iter()`, are temporaries.
+fn is_place(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(_) | Expr::Field(_) | Expr::Index(_) => true,
+        Expr::Unary(ExprUnary {
+            op: UnOp::Deref(_), ..
+        }) => true,
+        Expr::Paren(ExprParen { expr, .. }) => is_place(expr),
+        _ => false,
+    }
+}
+
+/// the receiver of the expression, if it is a call `receiver.is_empty()`
+fn is_empty_receiver(expr: &Expr) -> Option<&Expr> {
+    method_call_receiver(expr, "is_empty")
+}
+
+/// the receiver of the expression, if it is a call `receiver.method()` of the method
+/// with the given name without any arguments
+fn method_call_receiver<'a>(expr: &'a Expr, method_name: &str) -> Option<&'a Expr> {
+    match expr {
+        Expr::MethodCall(ExprMethodCall {
+            receiver,
+            method,
+            turbofish: None,
+            args,
+            ..
+        }) if args.is_empty() && method == method_name => Some(receiver),
+        _ => None,
     }
 }
@@ -1,4 +1,5 @@
 use proc_macro2::Span;
+use syn::fold::Fold;
 use syn::{Expr, Macro, MacroDelimiter};
 
 use crate::macro_parsing::assertion::Assertion;
@@ -17,33 +18,45 @@ pub struct AssertionMacro {
     /// the actual assertion. This contains the interesting stuff
     /// of what will be replaced
     pub assertion: Assertion,
+    /// true if the original assertion was one of the `debug_assert*!` macros,
+    /// which must only be checked when debug assertions are enabled
+    pub debug_only: bool,
 }
 
 impl AssertionMacro {
     /// Convenience constructor
-    pub fn new(assrt: Assertion, span: Span, info_args: Vec<Expr>) -> Self {
+    pub fn new(assrt: Assertion, span: Span, info_args: Vec<Expr>, debug_only: bool) -> Self {
         Self {
             assertion: assrt,
             span,
             info_args,
+            debug_only,
+        }
+    }
+
+    /// apply the given fold to the assertion and the info arguments, so that assertions
+    /// nested inside of them are replaced, too.
+    pub fn fold_with<F: Fold>(self, folder: &mut F) -> Self {
+        Self {
+            assertion: self.assertion.fold_with(folder),
+            info_args: self
+                .info_args
+                .into_iter()
+                .map(|arg| folder.fold_expr(arg))
+                .collect(),
+            ..self
         }
     }
 
     /// replace the macro invocation by the appropriate __assertify! or __checkify! invocations
-    /// of the supercrate
+    /// (or their `__debug_` counterparts) of the supercrate
     /// # Arguments
     /// * `assert2_macro_path`: the full path to the assertify or checkify macros
     pub fn assert2ify_with(self, assert2_macro_path: syn::Path) -> Macro {
         let info_args = self.info_args;
 
-        let tokens = match self.assertion {
-            Assertion::AssertBinary { lhs, operator, rhs } => {
-                quote_spanned! {self.span => #lhs #operator #rhs, #(#info_args),* }
-            }
-            Assertion::AssertUnary { expr } => {
-                quote_spanned! {self.span => #expr, #(#info_args),* }
-            }
-        };
+        let condition = self.assertion.condition_tokens();
+        let tokens = quote_spanned! {self.span => #condition, #(#info_args),* };
 
         Macro {
             path: assert2_macro_path,
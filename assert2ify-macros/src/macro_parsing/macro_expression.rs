@@ -52,6 +52,13 @@ impl TryFrom<Macro> for MacroExpression {
     /// Parsing the assertions might fail if the assertions are used incorrectly, e.g.
     /// if assert_eq! is used with just one argument. In this case we report an error.
     fn try_from(mac: Macro) -> Result<Self, Self::Error> {
+        let macro_kind = infer_macro_kind_from_path(&mac.path);
+        // only the arguments of assertions have to be comma separated expressions,
+        // so any other macro is passed through before we try parsing its arguments
+        if !macro_kind.is_assertion() {
+            return Ok(MacroExpression::new_other(mac));
+        }
+
         // get the span and parse the macro arguments
         let span = mac.span();
         let mut macro_arguments = mac
@@ -60,7 +67,6 @@ impl TryFrom<Macro> for MacroExpression {
 
         let create_compile_error = |err_msg| syn::Error::new(span, err_msg);
 
-        let macro_kind = infer_macro_kind_from_path(&mac.path);
         if macro_kind.is_binary_assertion() {
             // binary assertions:
             // the arguments inside assert_eq!(...) or assert_ne!(...)
@@ -79,8 +85,9 @@ impl TryFrom<Macro> for MacroExpression {
                 Assertion::new_binary(lhs, operator, rhs),
                 span,
                 info_args,
+                macro_kind.is_debug_assertion(),
             )))
-        } else if macro_kind.is_assertion() {
+        } else {
             // all kinds of unary assertions:
             // Remark: assert!(matches!(...)) asertions are handled by the __assertify!
             // and __checkify! macros in the crate one level above
@@ -93,10 +100,8 @@ impl TryFrom<Macro> for MacroExpression {
                 Assertion::new_assert(expr),
                 span,
                 info_args,
+                macro_kind.is_debug_assertion(),
             )))
-        } else {
-            // no assertions: just pass them though unaltered
-            Ok(MacroExpression::new_other(mac))
         }
     }
 }
@@ -0,0 +1,137 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::{Expr, Ident, Macro, Pat, Path, Token};
+
+use crate::detail::METAVARIABLE_PLACEHOLDER_PREFIX;
+
+/// The parsed arguments of a `matches!(expression, pattern if guard)` macro. We are mostly
+/// interested in the pattern, the replacement itself is done by the macros of the supercrate.
+pub struct MatchesMacro {
+    /// the path of the macro, like `matches` or `std::matches`
+    path: Path,
+    expression: Expr,
+    /// the alternatives of the pattern, i.e. `A | B` gives two alternatives. A leading `|` is
+    /// dropped, because the macros of the supercrate can't match it.
+    pub patterns: Punctuated<Pat, Token![|]>,
+    guard: Option<(Token![if], Expr)>,
+}
+
+impl MatchesMacro {
+    /// try to parse the arguments of the given macro if it is a `matches!` macro,
+    /// optionally qualified as `std::matches!` or `core::matches!`.
+    /// # Returns
+    /// The parsed arguments or None if this is not a `matches!` macro or it could not be parsed.
+    pub fn try_from_macro(mac: &Macro) -> Option<Self> {
+        let segments: Vec<&Ident> = mac.path.segments.iter().map(|s| &s.ident).collect();
+        let is_matches = match segments.as_slice() {
+            [name] => *name == "matches",
+            [krate, name] => (*krate == "std" || *krate == "core") && *name == "matches",
+            _ => false,
+        };
+        if is_matches {
+            let (expression, patterns, guard) = mac.parse_body_with(parse_arguments).ok()?;
+            Some(Self {
+                path: mac.path.clone(),
+                expression,
+                patterns,
+                guard,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// the identifiers of the variables that are bound by the pattern. All alternatives of a
+    /// pattern must bind the same variables, so it is enough to look at the first one.
+    pub fn bindings(&self) -> Vec<Ident> {
+        self.patterns.first().map(bindings_of).unwrap_or_default()
+    }
+}
+
+/// the identifiers of the variables that are bound by the given pattern
+pub fn bindings_of(pattern: &Pat) -> Vec<Ident> {
+    let mut bindings = Vec::new();
+    collect_bindings(pattern, &mut bindings);
+    bindings
+}
+
+/// the arguments `expression, pattern if guard` of the `matches!` macro
+#[allow(clippy::type_complexity)]
+fn parse_arguments(
+    input: ParseStream,
+) -> syn::Result<(Expr, Punctuated<Pat, Token![|]>, Option<(Token![if], Expr)>)> {
+    let expression = input.parse()?;
+    input.parse::<Token![,]>()?;
+    input.parse::<Option<Token![|]>>()?;
+    let patterns = Punctuated::parse_separated_nonempty(input)?;
+    let guard = match input.parse::<Option<Token![if]>>()? {
+        Some(if_token) => Some((if_token, input.parse()?)),
+        None => None,
+    };
+    input.parse::<Option<Token![,]>>()?;
+    Ok((expression, patterns, guard))
+}
+
+/// The macro invocation `matches!(expression, pattern if guard)` with the same path as the
+/// original, but without the leading `|` of the pattern and the trailing comma
+impl ToTokens for MatchesMacro {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let path = &self.path;
+        let expression = &self.expression;
+        let patterns = &self.patterns;
+        let guard = self
+            .guard
+            .as_ref()
+            .map(|(if_token, guard)| quote! {#if_token #guard});
+        tokens.extend(quote! {#path!(#expression, #patterns #guard)});
+    }
+}
+
+/// recursively collect the identifiers that are bound in the given pattern.
+/// Identifiers starting with an uppercase letter are assumed to be constants or
+/// enum variants like `None`, so they are not counted as bindings.
+fn collect_bindings(pattern: &Pat, bindings: &mut Vec<Ident>) {
+    match pattern {
+        Pat::Ident(pat_ident) => {
+            let name = pat_ident.ident.to_string();
+            let is_binding = name
+                .trim_start_matches("r#")
+                .starts_with(|c: char| c.is_lowercase() || c == '_');
+            // metavariables of macro definitions might be patterns themselves
+            if is_binding && !name.starts_with(METAVARIABLE_PLACEHOLDER_PREFIX) {
+                bindings.push(pat_ident.ident.clone());
+            }
+            if let Some((_, subpattern)) = &pat_ident.subpat {
+                collect_bindings(subpattern, bindings);
+            }
+        }
+        Pat::Box(pat_box) => collect_bindings(&pat_box.pat, bindings),
+        Pat::Or(pat_or) => {
+            if let Some(case) = pat_or.cases.first() {
+                collect_bindings(case, bindings);
+            }
+        }
+        Pat::Reference(pat_reference) => collect_bindings(&pat_reference.pat, bindings),
+        Pat::Slice(pat_slice) => pat_slice
+            .elems
+            .iter()
+            .for_each(|elem| collect_bindings(elem, bindings)),
+        Pat::Struct(pat_struct) => pat_struct
+            .fields
+            .iter()
+            .for_each(|field| collect_bindings(&field.pat, bindings)),
+        Pat::Tuple(pat_tuple) => pat_tuple
+            .elems
+            .iter()
+            .for_each(|elem| collect_bindings(elem, bindings)),
+        Pat::TupleStruct(pat_tuple_struct) => pat_tuple_struct
+            .pat
+            .elems
+            .iter()
+            .for_each(|elem| collect_bindings(elem, bindings)),
+        Pat::Type(pat_type) => collect_bindings(&pat_type.pat, bindings),
+        _ => {}
+    }
+}
@@ -1,3 +1,5 @@
 pub mod assertion;
 pub mod assertion_macro;
 pub mod macro_expression;
+pub mod matches_macro;
+pub mod panic_idiom;
@@ -0,0 +1,435 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote_spanned, ToTokens};
+use syn::fold::Fold;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Arm, Block, Expr, ExprIf, ExprMatch, Ident, Item, Lit, Macro, MacroDelimiter, Pat, PatIdent,
+    PatOr, Stmt, Token,
+};
+
+use crate::detail::is_std_macro;
+use crate::macro_parsing::assertion::Assertion;
+use crate::macro_parsing::assertion_macro::AssertionMacro;
+use crate::macro_parsing::matches_macro::bindings_of;
+
+/// The idiom `if condition { panic!(...) }`, which is translated into the equivalent assertion
+/// on the negated condition, like `assert!(!condition, ...)`. The arguments of the panic become
+/// the info arguments of the assertion, and the original `panic!` is kept, so that a failed
+/// assertion panics with the same payload as before.
+pub struct IfPanicIdiom {
+    /// the assertion on the negated condition
+    pub assertion: AssertionMacro,
+    /// the original `panic!` macro
+    panic: Macro,
+}
+
+impl IfPanicIdiom {
+    /// try to parse the idiom from an `if` expression
+    /// # Returns
+    /// The idiom or None if the expression is not an `if` without an `else`, whose body is a
+    /// single `panic!` with no arguments or a format string and its arguments.
+    pub fn try_from_if(expr_if: &ExprIf) -> Option<Self> {
+        if expr_if.else_branch.is_some()
+            || !expr_if.attrs.is_empty()
+            || matches!(expr_if.cond.as_ref(), Expr::Let(_))
+        {
+            return None;
+        }
+        let panic =
+            single_macro_of(&expr_if.then_branch).filter(|mac| is_std_macro(&mac.path, "panic"))?;
+        Some(Self {
+            assertion: AssertionMacro::new(
+                Assertion::new_negated(*expr_if.cond.clone()),
+                expr_if.if_token.span(),
+                diverging_arguments(panic)?,
+                false,
+            ),
+            panic: panic.clone(),
+        })
+    }
+
+    /// apply the given fold to the assertion and the panic, so that the assertions inside of
+    /// them are replaced, too
+    pub fn fold_with<F: Fold>(self, folder: &mut F) -> Self {
+        Self {
+            assertion: self.assertion.fold_with(folder),
+            panic: folder.fold_macro(self.panic),
+        }
+    }
+
+    /// replace the idiom by the invocation `path!(@if_panic(panic!(...)) [info_args...] condition)`
+    /// of the `__assertify!` or `__checkify!` macros of the supercrate. Assertions panic with the
+    /// original `panic!` after printing the failure, so its arguments are evaluated twice then.
+    pub fn assert2ify_with(self, assert2_macro_path: syn::Path) -> Macro {
+        let span = self.assertion.span;
+        let panic = &self.panic;
+        let info_args = &self.assertion.info_args;
+        let condition = self.assertion.assertion.condition_tokens();
+        Macro {
+            path: assert2_macro_path,
+            bang_token: syn::token::Bang { spans: [span; 1] },
+            delimiter: MacroDelimiter::Paren(syn::token::Paren { span }),
+            tokens: quote_spanned! {span => @if_panic(#panic) [#(#info_args),*] #condition},
+        }
+    }
+}
+
+/// A value that must match a pattern, because the code diverges with `panic!` or `unreachable!`
+/// otherwise. This is one of the idioms
+/// * `match expression { pattern => body, _ => panic!(...) }`
+/// * `if let pattern = expression { body } else { unreachable!(...) }`
+/// * `let pattern = expression else { panic!(...) };`
+///
+/// Those are translated into a `match` on the value, which binds the same variables and prints
+/// the value like `let_assert!(pattern = expression, ...)` does if it does not match the pattern.
+/// After that, the original code diverges just like before, so the panic keeps its message.
+pub struct LetAssertIdiom {
+    /// the span of the original `match`, `if` or `let`
+    pub span: Span,
+    pattern: Pat,
+    expression: Expr,
+    /// the arguments of the diverging macro, which become the info arguments of the failure
+    info_args: Vec<Expr>,
+    /// the statements that are executed if the value matches the pattern, which are
+    /// empty for `let ... else`
+    body: Vec<Stmt>,
+    /// the original arms that diverge, which is a single arm `_ => else_branch` for `if let`
+    /// and `let ... else`
+    diverging_arms: Vec<Arm>,
+}
+
+impl LetAssertIdiom {
+    /// try to parse the idiom from a match expression. The arm that does not diverge must
+    /// not have a guard, and all arms before it must diverge and match different enum variants
+    /// than it does (like `None` and `Some(value)`), so that the pattern alone decides whether
+    /// the code diverges. The arguments of the diverging macro are kept if there is just one
+    /// diverging arm and it does not bind any variables, which the arguments might refer to.
+    pub fn try_from_match(expr_match: &ExprMatch) -> Option<Self> {
+        if !expr_match.attrs.is_empty() {
+            return None;
+        }
+        let arms = &expr_match.arms;
+        let position = arms
+            .iter()
+            .position(|arm| diverging_arguments_of_expr(&arm.body).is_none())?;
+        let matching_arm = &arms[position];
+        if matching_arm.guard.is_some() {
+            return None;
+        }
+        if position > 0 {
+            let variants = variants_of(&matching_arm.pat)?;
+            let disjoint = arms[..position].iter().all(|arm| {
+                matches!(variants_of(&arm.pat), Some(others) if others.iter().all(|other| !variants.contains(other)))
+            });
+            if !disjoint {
+                return None;
+            }
+        }
+        let diverging_arms = arms
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != position)
+            .map(|(_, arm)| Some((&arm.pat, diverging_arguments_of_expr(&arm.body)?)))
+            .collect::<Option<Vec<_>>>()?;
+        if diverging_arms.is_empty() {
+            return None;
+        }
+        let info_args = match diverging_arms.as_slice() {
+            [(pattern, arguments)] if bindings_of(pattern).is_empty() => arguments.clone(),
+            _ => Vec::new(),
+        };
+        Some(Self {
+            span: expr_match.match_token.span(),
+            pattern: matching_arm.pat.clone(),
+            expression: *expr_match.expr.clone(),
+            info_args,
+            body: vec![Stmt::Expr(*matching_arm.body.clone())],
+            diverging_arms: arms
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != position)
+                .map(|(_, arm)| arm.clone())
+                .collect(),
+        })
+    }
+
+    /// try to parse the idiom from an `if let` expression, whose `else` branch diverges
+    pub fn try_from_if_let(expr_if: &ExprIf) -> Option<Self> {
+        let expr_let = match expr_if.cond.as_ref() {
+            Expr::Let(expr_let) if expr_if.attrs.is_empty() => expr_let,
+            _ => return None,
+        };
+        let else_branch = expr_if.else_branch.as_ref()?.1.as_ref();
+        let info_args = match else_branch {
+            Expr::Block(expr_block)
+                if expr_block.attrs.is_empty() && expr_block.label.is_none() =>
+            {
+                diverging_arguments(single_macro_of(&expr_block.block)?)?
+            }
+            _ => return None,
+        };
+        Some(Self {
+            span: expr_if.if_token.span(),
+            pattern: expr_let.pat.clone(),
+            expression: *expr_let.expr.clone(),
+            info_args,
+            body: expr_if.then_branch.stmts.clone(),
+            diverging_arms: vec![syn::parse_quote! {_ => #else_branch}],
+        })
+    }
+
+    /// try to parse the idiom from a `let ... else` statement, whose `else` block diverges.
+    /// Our version of syn does not parse those statements, it gives them to us as verbatim tokens.
+    pub fn try_from_let_else(tokens: &TokenStream) -> Option<Self> {
+        let let_else: LetElse = syn::parse2(tokens.clone()).ok()?;
+        let else_block = &let_else.else_block;
+        Some(Self {
+            span: let_else.let_token.span(),
+            info_args: diverging_arguments(single_macro_of(else_block)?)?,
+            diverging_arms: vec![syn::parse_quote! {_ => #else_block}],
+            pattern: let_else.pattern,
+            expression: let_else.expression,
+            body: Vec::new(),
+        })
+    }
+
+    /// apply the given fold to the expression, the info arguments, the body and the diverging
+    /// arms, so that the assertions inside of them are replaced, too
+    pub fn fold_with<F: Fold>(self, folder: &mut F) -> Self {
+        Self {
+            expression: folder.fold_expr(self.expression),
+            info_args: self
+                .info_args
+                .into_iter()
+                .map(|arg| folder.fold_expr(arg))
+                .collect(),
+            body: self
+                .body
+                .into_iter()
+                .map(|stmt| folder.fold_stmt(stmt))
+                .collect(),
+            diverging_arms: self
+                .diverging_arms
+                .into_iter()
+                .map(|arm| folder.fold_arm(arm))
+                .collect(),
+            ..self
+        }
+    }
+
+    /// the `match` that replaces the `match` or `if let` expression. It evaluates the body if the
+    /// value matches the pattern and invokes the macro with the given path and the diverging arms
+    /// otherwise, see [LetAssertIdiom::failure_with]. The value is matched in place, so it is not moved
+    /// unless the pattern moves it, just like in the original code.
+    pub fn into_match_with(self, failure_macro_path: syn::Path) -> Expr {
+        let failure = self.failure_with(failure_macro_path);
+        let pattern = self.pattern_without_leading_vert();
+        let expression = &self.expression;
+        let body = &self.body;
+        Expr::Match(syn::parse_quote_spanned! {self.span =>
+            match #expression {
+                #pattern => { #(#body)* }
+                #failure
+            }
+        })
+    }
+
+    /// the statement that replaces `let pattern = expression else { ... };`. It is the statement
+    /// `let (bindings...) = match expression { pattern => (bindings...), ... };`, which binds the
+    /// same variables in the scope of the caller and invokes the macro with the given path if the
+    /// value does not match, see [LetAssertIdiom::failure_with].
+    pub fn into_let_with(self, failure_macro_path: syn::Path) -> Stmt {
+        let failure = self.failure_with(failure_macro_path);
+        let expression = &self.expression;
+        let bindings = bindings_of(&self.pattern);
+        if bindings.is_empty() {
+            let pattern = self.pattern_without_leading_vert();
+            return syn::parse_quote_spanned! {self.span =>
+                match #expression {
+                    #pattern => {}
+                    #failure
+                };
+            };
+        }
+        // the mutability of the bindings moves to the variables of the `let` statement,
+        // otherwise the compiler complains about variables that do not need to be mutable
+        let mut immutable_bindings = ImmutableBindings::default();
+        let pattern = immutable_bindings.fold_pat(self.pattern_without_leading_vert());
+        let variables = bindings.iter().map(|binding| {
+            if immutable_bindings.mutable.contains(binding) {
+                quote_spanned! {self.span => mut #binding}
+            } else {
+                binding.to_token_stream()
+            }
+        });
+        syn::parse_quote_spanned! {self.span =>
+            let (#(#variables,)*) = match #expression {
+                #pattern => (#(#bindings,)*),
+                #failure
+            };
+        }
+    }
+
+    /// the fallback arms of the `match`. The first one invokes the macro with the given path like
+    /// `failure!(value, expression, [pattern], [info_args...])` in its guard. That macro must print
+    /// the value like `let_assert!` does and give `false`, so that the original diverging arms
+    /// come next. Those bind their variables and panic just like before, even for checks, because
+    /// there are no values to bind otherwise.
+    fn failure_with(&self, failure_macro_path: syn::Path) -> TokenStream {
+        // the value must not shadow the variables that the info arguments refer to
+        let value = Ident::new("value", Span::mixed_site());
+        let pattern = &self.pattern;
+        let expression = &self.expression;
+        let info_args = &self.info_args;
+        let diverging_arms = &self.diverging_arms;
+        quote_spanned! {self.span =>
+            #[allow(unreachable_patterns)]
+            ref #value if #failure_macro_path!(#value, #expression, [#pattern], [#(#info_args),*]) => ::std::unreachable!(),
+            #(#diverging_arms)*
+        }
+    }
+
+    /// the pattern without the leading `|` of its alternatives, which is only allowed on the top
+    /// level of the arms of a `match`
+    fn pattern_without_leading_vert(&self) -> Pat {
+        match &self.pattern {
+            Pat::Or(pat_or) => Pat::Or(PatOr {
+                leading_vert: None,
+                ..pat_or.clone()
+            }),
+            pattern => pattern.clone(),
+        }
+    }
+}
+
+/// A fold that removes the `mut` of the variables that a pattern binds by value and
+/// remembers those variables
+#[derive(Default)]
+struct ImmutableBindings {
+    mutable: Vec<Ident>,
+}
+
+impl Fold for ImmutableBindings {
+    fn fold_pat_ident(&mut self, mut pat_ident: PatIdent) -> PatIdent {
+        if pat_ident.by_ref.is_none() && pat_ident.mutability.take().is_some() {
+            self.mutable.push(pat_ident.ident.clone());
+        }
+        syn::fold::fold_pat_ident(self, pat_ident)
+    }
+}
+
+/// The statement `let pattern = expression else { ... }` without the semicolon
+struct LetElse {
+    let_token: Token![let],
+    pattern: Pat,
+    expression: Expr,
+    else_block: Block,
+}
+
+impl Parse for LetElse {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let let_token = input.parse()?;
+        let leading_vert: Option<Token![|]> = input.parse()?;
+        let mut cases: Punctuated<Pat, Token![|]> = Punctuated::parse_separated_nonempty(input)?;
+        let pattern = if cases.len() == 1 && leading_vert.is_none() {
+            cases.pop().expect("there is one case").into_value()
+        } else {
+            Pat::Or(PatOr {
+                attrs: Vec::new(),
+                leading_vert,
+                cases,
+            })
+        };
+        input.parse::<Token![=]>()?;
+        let expression = input.parse()?;
+        input.parse::<Token![else]>()?;
+        let else_block = input.parse()?;
+        Ok(Self {
+            let_token,
+            pattern,
+            expression,
+            else_block,
+        })
+    }
+}
+
+/// the names of the enum variants that the pattern matches, like `Some` for `Some(value)`, if
+/// the pattern only matches enum variants. Identifiers starting with an uppercase letter, like
+/// `None`, are assumed to be enum variants.
+fn variants_of(pattern: &Pat) -> Option<Vec<&Ident>> {
+    fn last_segment(path: &syn::Path) -> Option<&Ident> {
+        path.segments.last().map(|segment| &segment.ident)
+    }
+    match pattern {
+        Pat::Path(pat_path) => Some(vec![last_segment(&pat_path.path)?]),
+        Pat::TupleStruct(pat_tuple_struct) => Some(vec![last_segment(&pat_tuple_struct.path)?]),
+        Pat::Struct(pat_struct) => Some(vec![last_segment(&pat_struct.path)?]),
+        Pat::Ident(pat_ident)
+            if pat_ident.subpat.is_none()
+                && pat_ident.by_ref.is_none()
+                && pat_ident.mutability.is_none()
+                && pat_ident.ident.to_string().starts_with(char::is_uppercase) =>
+        {
+            Some(vec![&pat_ident.ident])
+        }
+        Pat::Or(pat_or) => pat_or
+            .cases
+            .iter()
+            .map(variants_of)
+            .collect::<Option<Vec<_>>>()
+            .map(|variants| variants.into_iter().flatten().collect()),
+        _ => None,
+    }
+}
+
+/// the macro in the block, if the block consists of nothing else
+fn single_macro_of(block: &Block) -> Option<&Macro> {
+    match block.stmts.as_slice() {
+        [Stmt::Expr(Expr::Macro(expr_macro))] | [Stmt::Semi(Expr::Macro(expr_macro), _)]
+            if expr_macro.attrs.is_empty() =>
+        {
+            Some(&expr_macro.mac)
+        }
+        [Stmt::Item(Item::Macro(item_macro))]
+            if item_macro.attrs.is_empty() && item_macro.ident.is_none() =>
+        {
+            Some(&item_macro.mac)
+        }
+        _ => None,
+    }
+}
+
+/// the arguments of the diverging macro that the expression consists of (maybe inside a block),
+/// see [diverging_arguments]
+fn diverging_arguments_of_expr(expr: &Expr) -> Option<Vec<Expr>> {
+    match expr {
+        Expr::Macro(expr_macro) if expr_macro.attrs.is_empty() => {
+            diverging_arguments(&expr_macro.mac)
+        }
+        Expr::Block(expr_block) if expr_block.attrs.is_empty() && expr_block.label.is_none() => {
+            diverging_arguments(single_macro_of(&expr_block.block)?)
+        }
+        _ => None,
+    }
+}
+
+/// the arguments of the macro if it is a `panic!` or `unreachable!` macro without arguments or
+/// with a format string and its arguments. A panic with any other payload can't be translated
+/// into an assertion.
+fn diverging_arguments(mac: &Macro) -> Option<Vec<Expr>> {
+    if !is_std_macro(&mac.path, "panic") && !is_std_macro(&mac.path, "unreachable") {
+        return None;
+    }
+    let arguments: Vec<Expr> = mac
+        .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+        .ok()?
+        .into_iter()
+        .collect();
+    match arguments.first() {
+        None => Some(arguments),
+        Some(Expr::Lit(expr_lit)) if matches!(expr_lit.lit, Lit::Str(_)) => Some(arguments),
+        Some(_) => None,
+    }
+}
@@ -0,0 +1,289 @@
+//! Approximate comparison of floating point numbers with an absolute, relative or ULP based
+//! tolerance, for the `assert_approx_eq!` family of macros.
+
+use std::fmt::{Debug, Write};
+
+use yansi::Paint;
+
+use crate::assertions::print::CheckExpression;
+
+/// the number of ULPs that two numbers may differ by if no tolerance is given
+const DEFAULT_ULPS: u64 = 4;
+
+/// A floating point number that can be compared approximately
+pub trait Float: Copy + Debug {
+    fn to_f64(self) -> f64;
+    /// the number of representable numbers between this number and the other number, which
+    /// is their difference in units in the last place (ULPs)
+    fn ulps_between(self, other: Self) -> u64;
+}
+
+impl Float for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn ulps_between(self, other: Self) -> u64 {
+        // map the bits to integers that are ordered like the numbers, with -0.0 and 0.0 both being 0
+        let ordered = |number: f64| {
+            let bits = number.to_bits() as i64;
+            if bits < 0 {
+                i64::MIN.wrapping_sub(bits)
+            } else {
+                bits
+            }
+        };
+        (i128::from(ordered(self)) - i128::from(ordered(other))).unsigned_abs() as u64
+    }
+}
+
+impl Float for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn ulps_between(self, other: Self) -> u64 {
+        let ordered = |number: f32| {
+            let bits = number.to_bits() as i32;
+            if bits < 0 {
+                i32::MIN.wrapping_sub(bits)
+            } else {
+                bits
+            }
+        };
+        (i64::from(ordered(self)) - i64::from(ordered(other))).unsigned_abs()
+    }
+}
+
+impl<T: Float> Float for &T {
+    fn to_f64(self) -> f64 {
+        (*self).to_f64()
+    }
+
+    fn ulps_between(self, other: Self) -> u64 {
+        (*self).ulps_between(*other)
+    }
+}
+
+/// The tolerance of an approximate comparison. Two numbers are approximately equal if their
+/// difference is within any of the given tolerances.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Tolerance {
+    abs: Option<f64>,
+    rel: Option<f64>,
+    ulps: Option<u64>,
+}
+
+impl Tolerance {
+    /// the maximum absolute difference `|left - right|`
+    pub fn abs(mut self, abs: impl Into<f64>) -> Self {
+        self.abs = Some(abs.into());
+        self
+    }
+
+    /// the maximum difference relative to the larger magnitude of left and right
+    pub fn rel(mut self, rel: impl Into<f64>) -> Self {
+        self.rel = Some(rel.into());
+        self
+    }
+
+    /// the maximum difference in units in the last place
+    pub fn ulps(mut self, ulps: u64) -> Self {
+        self.ulps = Some(ulps);
+        self
+    }
+
+    /// the given tolerance or a tolerance of [DEFAULT_ULPS] ULPs if none was given
+    fn or_default(self) -> Self {
+        if self.abs.is_none() && self.rel.is_none() && self.ulps.is_none() {
+            Self::default().ulps(DEFAULT_ULPS)
+        } else {
+            self
+        }
+    }
+}
+
+/// Two numbers that are not approximately equal
+pub struct ApproxFailure {
+    left: String,
+    right: String,
+    difference: f64,
+    ulps: u64,
+    tolerance: Tolerance,
+    /// whether one of the numbers is NaN
+    nan: bool,
+}
+
+/// Compare the numbers approximately. NaN is not approximately equal to anything (not even NaN),
+/// and infinities are only equal to themselves.
+/// # Returns
+/// `Ok(())` if the numbers are approximately equal and the reason why they are not otherwise
+pub fn approx_eq<T: Float>(left: T, right: T, tolerance: Tolerance) -> Result<(), ApproxFailure> {
+    let tolerance = tolerance.or_default();
+    let (left_value, right_value) = (left.to_f64(), right.to_f64());
+    let nan = left_value.is_nan() || right_value.is_nan();
+    let difference = (left_value - right_value).abs();
+    let ulps = left.ulps_between(right);
+    let within_tolerance = left_value == right_value
+        || (difference.is_finite()
+            && (matches!(tolerance.abs, Some(abs) if difference <= abs)
+                || matches!(tolerance.rel, Some(rel) if difference <= rel * left_value.abs().max(right_value.abs()))
+                || matches!(tolerance.ulps, Some(max_ulps) if ulps <= max_ulps)));
+    if !nan && within_tolerance {
+        Ok(())
+    } else {
+        Err(ApproxFailure {
+            left: format!("{:?}", left),
+            right: format!("{:?}", right),
+            difference,
+            ulps,
+            tolerance,
+            nan,
+        })
+    }
+}
+
+/// The expression of a failed approximate comparison, whose expansion shows both numbers, their
+/// difference and the tolerance
+pub struct ApproxExpr<'a> {
+    pub failure: ApproxFailure,
+    /// the left hand side as it was written in the code
+    pub left_expr: &'a str,
+    /// the right hand side as it was written in the code
+    pub right_expr: &'a str,
+    /// the tolerance as it was written in the code, like `abs = 1e-9`, which might be empty
+    pub tolerance_expr: &'a str,
+}
+
+impl CheckExpression for ApproxExpr<'_> {
+    fn write_expression(&self, buffer: &mut String) {
+        write!(
+            buffer,
+            "{}, {}",
+            Paint::cyan(self.left_expr),
+            Paint::yellow(self.right_expr)
+        )
+        .unwrap();
+        if !self.tolerance_expr.is_empty() {
+            write!(buffer, ", {}", self.tolerance_expr).unwrap();
+        }
+    }
+
+    fn write_expansion(&self, buffer: &mut String) {
+        let failure = &self.failure;
+        writeln!(buffer, "with expansion:").unwrap();
+        writeln!(
+            buffer,
+            "  {} {} {}",
+            Paint::cyan(&failure.left),
+            Paint::blue("≈").bold(),
+            Paint::yellow(&failure.right)
+        )
+        .unwrap();
+        if failure.nan {
+            write!(
+                buffer,
+                "{}",
+                "Note: NaN is not approximately equal to anything".red()
+            )
+            .unwrap();
+            return;
+        }
+        writeln!(
+            buffer,
+            "  {} {} {:?} ({} ULPs)",
+            Paint::magenta("difference"),
+            Paint::blue("=").bold(),
+            failure.difference,
+            failure.ulps
+        )
+        .unwrap();
+        let tolerance = failure.tolerance;
+        let tolerances: Vec<String> = vec![
+            tolerance.abs.map(|abs| format!("abs = {:?}", abs)),
+            tolerance.rel.map(|rel| format!("rel = {:?}", rel)),
+            tolerance.ulps.map(|ulps| format!("ulps = {}", ulps)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write!(
+            buffer,
+            "  {} {} {}",
+            Paint::magenta("tolerance"),
+            Paint::blue("=").bold(),
+            tolerances.join(", ")
+        )
+        .unwrap();
+    }
+}
+
+/// Assert that two floating point numbers are approximately equal. The tolerance is given as any
+/// combination of `abs = ..` (the maximum absolute difference), `rel = ..` (the maximum difference
+/// relative to the larger magnitude of both numbers) and `ulps = ..` (the maximum difference in
+/// units in the last place). The numbers are approximately equal if they are within any of the given
+/// tolerances. If no tolerance is given, the numbers may differ by 4 ULPs.
+///
+/// NaN is not approximately equal to anything, not even to NaN, and infinities are only
+/// equal to themselves. Additional arguments are a format string and its arguments, just like
+/// for `assert_eq!`.
+///
+/// If the assertion fails, both numbers, their difference and the tolerance are printed.
+/// ```rust
+/// # use assert2ify::assert_approx_eq;
+/// assert_approx_eq!(0.1 + 0.2, 0.3);
+/// assert_approx_eq!(1.0f32, 1.001, abs = 0.01);
+/// assert_approx_eq!(1000.0, 1000.1, rel = 1e-3, "{}", "close enough");
+/// ```
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(, $($rest:tt)*)?) => {
+        $crate::__approx_eq!(assert, "assert_approx_eq", $left, $right, [] $($($rest)*)?)
+    };
+}
+
+/// The approximate equivalent of the `check!` macro, which does not panic right away but makes the
+/// test fail at the end of the current scope. See [assert_approx_eq] for the arguments.
+#[macro_export]
+macro_rules! check_approx_eq {
+    ($left:expr, $right:expr $(, $($rest:tt)*)?) => {
+        $crate::__approx_eq!(check, "check_approx_eq", $left, $right, [] $($($rest)*)?)
+    };
+}
+
+/// The debug variant of [assert_approx_eq], which only checks the numbers if debug assertions are enabled
+#[macro_export]
+macro_rules! debug_assert_approx_eq {
+    ($left:expr, $right:expr $(, $($rest:tt)*)?) => {
+        if ::std::cfg!(debug_assertions) {
+            $crate::__approx_eq!(debug_assert, "debug_assert_approx_eq", $left, $right, [] $($($rest)*)?)
+        }
+    };
+}
+
+/// The debug variant of [check_approx_eq], which only checks the numbers if debug assertions are enabled
+#[macro_export]
+macro_rules! debug_check_approx_eq {
+    ($left:expr, $right:expr $(, $($rest:tt)*)?) => {
+        $crate::__approx_eq!(debug_check, "debug_check_approx_eq", $left, $right, [] $($($rest)*)?)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper for the `assert_approx_eq!` family of macros, which collects the tolerances `abs = ..`,
+/// `rel = ..` and `ulps = ..` in the brackets. The arguments after the tolerances are the info arguments.
+macro_rules! __approx_eq {
+    ($style:ident, $macro_name:expr, $left:expr, $right:expr, [$($tolerance:tt)*] abs = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__approx_eq!($style, $macro_name, $left, $right, [$($tolerance)* abs = $value,] $($($rest)*)?)
+    };
+    ($style:ident, $macro_name:expr, $left:expr, $right:expr, [$($tolerance:tt)*] rel = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__approx_eq!($style, $macro_name, $left, $right, [$($tolerance)* rel = $value,] $($($rest)*)?)
+    };
+    ($style:ident, $macro_name:expr, $left:expr, $right:expr, [$($tolerance:tt)*] ulps = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__approx_eq!($style, $macro_name, $left, $right, [$($tolerance)* ulps = $value,] $($($rest)*)?)
+    };
+    ($style:ident, $macro_name:expr, $left:expr, $right:expr, [$($tolerance:tt)*] $($info_args:tt)*) => {
+        $crate::__conclude!($style, $crate::__check_conjunct!($macro_name, [$($info_args)*] @approx_eq($left, $right, [$($tolerance)*])))
+    };
+}
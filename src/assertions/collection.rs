@@ -0,0 +1,21 @@
+//! Helpers for the checks on `collection.len()` and `collection.is_empty()`, whose output
+//! shows the contents of the collection.
+
+use std::fmt::Debug;
+
+/// the maximum number of characters of the debug output of a collection that are printed
+const MAX_COLLECTION_DEBUG_LENGTH: usize = 256;
+
+/// The debug output of the collection, which is truncated to [MAX_COLLECTION_DEBUG_LENGTH]
+/// characters, so that huge collections don't flood the output.
+pub fn truncated_debug<T: Debug + ?Sized>(collection: &T) -> String {
+    let debug = format!("{:?}", collection);
+    match debug.char_indices().nth(MAX_COLLECTION_DEBUG_LENGTH) {
+        Some((end, _)) => format!(
+            "{}... ({} more characters)",
+            &debug[..end],
+            debug[end..].chars().count()
+        ),
+        None => debug,
+    }
+}
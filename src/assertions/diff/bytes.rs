@@ -0,0 +1,224 @@
+//! The difference between two byte slices as a hex dump with offsets and the ASCII representation
+//! of the bytes, in which the differing bytes are highlighted.
+
+use std::fmt::Write;
+use std::ops::Deref;
+
+use yansi::{Paint, Style};
+
+use crate::assertions::diff::{Wrap, WriteDiff};
+
+/// the number of bytes in a row of the hex dump
+const ROW_LENGTH: usize = 16;
+/// the maximum number of differing rows that are shown
+const MAX_ROWS: usize = 8;
+
+/// Bytes that we can show in a hex dump
+pub trait Bytes {
+    fn bytes(&self) -> &[u8];
+}
+
+impl Bytes for [u8] {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<const N: usize> Bytes for [u8; N] {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Bytes for Vec<u8> {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<B: Bytes + ?Sized> Bytes for &B {
+    fn bytes(&self) -> &[u8] {
+        (**self).bytes()
+    }
+}
+
+impl<B: Bytes + ?Sized> Bytes for &mut B {
+    fn bytes(&self) -> &[u8] {
+        (**self).bytes()
+    }
+}
+
+impl<B: Bytes + ?Sized> Bytes for Box<B> {
+    fn bytes(&self) -> &[u8] {
+        (**self).bytes()
+    }
+}
+
+/// Gives the difference of the wrapped values if both are bytes, see [Bytes] and
+/// [crate::assertions::diff::probes] for the order of the probes
+pub trait IsBytes {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>>;
+}
+
+/// Gives the difference of the wrapped values if both dereference to a byte slice, like the
+/// `Bytes` type of the bytes crate. See [IsBytes].
+pub trait IsDerefBytes {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>>;
+}
+
+impl<Left: Bytes + ?Sized, Right: Bytes + ?Sized> IsBytes for &&&&&&Wrap<'_, Left, Right> {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>> {
+        let diff = BytesDiff::new(self.0.bytes(), self.1.bytes())?;
+        Some(Box::new(diff))
+    }
+}
+
+impl<Left, Right> IsDerefBytes for &&&&&Wrap<'_, Left, Right>
+where
+    Left: Deref<Target = [u8]> + ?Sized,
+    Right: Deref<Target = [u8]> + ?Sized,
+{
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>> {
+        let diff = BytesDiff::new(self.0, self.1)?;
+        Some(Box::new(diff))
+    }
+}
+
+/// The difference between two byte slices, i.e. the rows of the hex dump in which they differ
+pub struct BytesDiff {
+    /// the offset of the first byte that differs or at which one of the slices ended
+    offset: usize,
+    left: Vec<u8>,
+    right: Vec<u8>,
+    /// the indices of the rows that differ
+    rows: Vec<usize>,
+}
+
+impl BytesDiff {
+    /// The difference between the given bytes.
+    /// # Returns
+    /// The difference or None if the bytes are the same
+    fn new(left: &[u8], right: &[u8]) -> Option<Self> {
+        if left == right {
+            return None;
+        }
+        let offset = left
+            .iter()
+            .zip(right)
+            .position(|(lhs, rhs)| lhs != rhs)
+            .unwrap_or_else(|| left.len().min(right.len()));
+        // the number of rows is the number of offsets at which a row starts
+        let row_count = (0..left.len().max(right.len())).step_by(ROW_LENGTH).count();
+        let rows = (offset / ROW_LENGTH..row_count)
+            .filter(|&row| row_of(left, row) != row_of(right, row))
+            .collect();
+        Some(Self {
+            offset,
+            left: left.to_vec(),
+            right: right.to_vec(),
+            rows,
+        })
+    }
+}
+
+/// the bytes in the row with the given index, which might be fewer than [ROW_LENGTH] (or none) at
+/// the end of the bytes
+fn row_of(bytes: &[u8], row: usize) -> &[u8] {
+    let start = (row * ROW_LENGTH).min(bytes.len());
+    let end = ((row + 1) * ROW_LENGTH).min(bytes.len());
+    &bytes[start..end]
+}
+
+impl WriteDiff for BytesDiff {
+    fn write_diff(&self, buffer: &mut String) {
+        writeln!(buffer, "with hex diff:").unwrap();
+        write!(
+            buffer,
+            "  first difference at offset {}",
+            Paint::blue(&format!("{:#x}", self.offset)).bold()
+        )
+        .unwrap();
+        if self.left.len() != self.right.len() {
+            write!(
+                buffer,
+                "\n  lengths differ: left has {} bytes, right has {} bytes",
+                Paint::red(&self.left.len()),
+                Paint::green(&self.right.len())
+            )
+            .unwrap();
+        }
+
+        let mut previous_row = None;
+        for &row in self.rows.iter().take(MAX_ROWS) {
+            if matches!(previous_row, Some(previous) if previous + 1 != row) {
+                write!(buffer, "\n  ...").unwrap();
+            }
+            let (left, right) = (row_of(&self.left, row), row_of(&self.right, row));
+            write_row(buffer, '-', row, left, right, Style::new().red());
+            write_row(buffer, '+', row, right, left, Style::new().green());
+            previous_row = Some(row);
+        }
+        if self.rows.len() > MAX_ROWS {
+            write!(
+                buffer,
+                "\n  ... ({} more differing rows)",
+                self.rows.len() - MAX_ROWS
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// write a row of the hex dump like `-00000010  48 65 6c 6c 6f  |Hello|` with the given marker
+/// (`-` or `+`) in the given style. The bytes that differ from the other row are highlighted.
+fn write_row(
+    buffer: &mut String,
+    marker: char,
+    row: usize,
+    bytes: &[u8],
+    other: &[u8],
+    style: Style,
+) {
+    let highlighted = style.bold().underline();
+    let style_of = |index: usize| {
+        if other.get(index) == bytes.get(index) {
+            style
+        } else {
+            highlighted
+        }
+    };
+    write!(
+        buffer,
+        "\n  {}",
+        format!("{}{:08x}", marker, row * ROW_LENGTH).paint(style)
+    )
+    .unwrap();
+    for index in 0..ROW_LENGTH {
+        // an additional space separates the offset and the two halves of the row
+        let separator = if index % (ROW_LENGTH / 2) == 0 {
+            "  "
+        } else {
+            " "
+        };
+        match bytes.get(index) {
+            Some(byte) => write!(
+                buffer,
+                "{}{}",
+                separator,
+                format!("{:02x}", byte).paint(style_of(index))
+            )
+            .unwrap(),
+            None => write!(buffer, "{}  ", separator).unwrap(),
+        }
+    }
+    write!(buffer, "  |").unwrap();
+    for (index, &byte) in bytes.iter().enumerate() {
+        let character = if byte.is_ascii_graphic() || byte == b' ' {
+            byte as char
+        } else {
+            '.'
+        };
+        write!(buffer, "{}", character.paint(style_of(index))).unwrap();
+    }
+    write!(buffer, "|").unwrap();
+}
@@ -0,0 +1,237 @@
+//! The difference between two maps or sets, which lists the keys that only one of them has and the
+//! keys with different values, independent of the order in which the maps iterate their entries.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{Debug, Write};
+
+use yansi::Paint;
+
+use crate::assertions::diff::{Wrap, WriteDiff};
+
+/// A map or set whose keys and values implement Debug
+pub trait MapLike {
+    /// the debug output of the keys and values of the map (None for sets), sorted by the debug
+    /// output of the keys, so that the order doesn't depend on the order of iteration
+    fn debug_entries(&self) -> Vec<(String, Option<String>)>;
+}
+
+/// A map or set whose keys implement Ord, so the entries can be sorted by key
+pub trait SortedMapLike {
+    /// the debug output of the keys and values of the map (None for sets), sorted by key
+    fn sorted_debug_entries(&self) -> Vec<(String, Option<String>)>;
+}
+
+/// the debug output of the given keys and values
+fn debug_entries<'a, K, V>(
+    entries: impl Iterator<Item = (&'a K, Option<&'a V>)>,
+) -> Vec<(String, Option<String>)>
+where
+    K: Debug + 'a,
+    V: Debug + 'a,
+{
+    entries
+        .map(|(key, value)| {
+            (
+                format!("{:?}", key),
+                value.map(|value| format!("{:?}", value)),
+            )
+        })
+        .collect()
+}
+
+impl<K: Debug, V: Debug, S> MapLike for HashMap<K, V, S> {
+    fn debug_entries(&self) -> Vec<(String, Option<String>)> {
+        let mut entries = debug_entries(self.iter().map(|(key, value)| (key, Some(value))));
+        entries.sort();
+        entries
+    }
+}
+
+impl<K: Debug, S> MapLike for HashSet<K, S> {
+    fn debug_entries(&self) -> Vec<(String, Option<String>)> {
+        let mut entries = debug_entries::<K, ()>(self.iter().map(|key| (key, None)));
+        entries.sort();
+        entries
+    }
+}
+
+impl<K: Debug, V: Debug> MapLike for BTreeMap<K, V> {
+    fn debug_entries(&self) -> Vec<(String, Option<String>)> {
+        let mut entries = debug_entries(self.iter().map(|(key, value)| (key, Some(value))));
+        entries.sort();
+        entries
+    }
+}
+
+impl<K: Debug> MapLike for BTreeSet<K> {
+    fn debug_entries(&self) -> Vec<(String, Option<String>)> {
+        let mut entries = debug_entries::<K, ()>(self.iter().map(|key| (key, None)));
+        entries.sort();
+        entries
+    }
+}
+
+impl<K: Ord + Debug, V: Debug, S> SortedMapLike for HashMap<K, V, S> {
+    fn sorted_debug_entries(&self) -> Vec<(String, Option<String>)> {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by_key(|(key, _)| *key);
+        debug_entries(entries.into_iter().map(|(key, value)| (key, Some(value))))
+    }
+}
+
+impl<K: Ord + Debug, S> SortedMapLike for HashSet<K, S> {
+    fn sorted_debug_entries(&self) -> Vec<(String, Option<String>)> {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort();
+        debug_entries::<K, ()>(entries.into_iter().map(|key| (key, None)))
+    }
+}
+
+impl<K: Ord + Debug, V: Debug> SortedMapLike for BTreeMap<K, V> {
+    fn sorted_debug_entries(&self) -> Vec<(String, Option<String>)> {
+        debug_entries(self.iter().map(|(key, value)| (key, Some(value))))
+    }
+}
+
+impl<K: Ord + Debug> SortedMapLike for BTreeSet<K> {
+    fn sorted_debug_entries(&self) -> Vec<(String, Option<String>)> {
+        debug_entries::<K, ()>(self.iter().map(|key| (key, None)))
+    }
+}
+
+impl<M: MapLike + ?Sized> MapLike for &M {
+    fn debug_entries(&self) -> Vec<(String, Option<String>)> {
+        (**self).debug_entries()
+    }
+}
+
+impl<M: SortedMapLike + ?Sized> SortedMapLike for &M {
+    fn sorted_debug_entries(&self) -> Vec<(String, Option<String>)> {
+        (**self).sorted_debug_entries()
+    }
+}
+
+/// Gives the difference of the wrapped values if both are maps or sets with keys that implement Ord,
+/// see [crate::assertions::diff::probes] for the order of the probes
+pub trait IsSortedMap {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>>;
+}
+
+/// Gives the difference of the wrapped values if both are maps or sets, see [IsSortedMap]
+pub trait IsMap {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>>;
+}
+
+impl<Left, Right> IsSortedMap for &&&Wrap<'_, Left, Right>
+where
+    Left: SortedMapLike + ?Sized,
+    Right: SortedMapLike + ?Sized,
+{
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>> {
+        let diff = MapDiff::new(self.0.sorted_debug_entries(), self.1.sorted_debug_entries())?;
+        Some(Box::new(diff))
+    }
+}
+
+impl<Left: MapLike + ?Sized, Right: MapLike + ?Sized> IsMap for &&Wrap<'_, Left, Right> {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>> {
+        let diff = MapDiff::new(self.0.debug_entries(), self.1.debug_entries())?;
+        Some(Box::new(diff))
+    }
+}
+
+/// The difference between two maps or sets, with the debug output of the keys (and values)
+pub struct MapDiff {
+    /// the entries whose keys only the left map has
+    only_left: Vec<(String, Option<String>)>,
+    /// the entries whose keys only the right map has
+    only_right: Vec<(String, Option<String>)>,
+    /// the keys that both maps have, but with different values in the left and right map
+    different_values: Vec<(String, String, String)>,
+}
+
+impl MapDiff {
+    /// The difference between the maps with the given debug output of their entries, in the order
+    /// in which they should be printed.
+    /// # Returns
+    /// The difference or None if the debug output of the maps is the same, which happens if e.g.
+    /// they contain NaNs. We can't point to the difference in this case.
+    fn new(
+        left: Vec<(String, Option<String>)>,
+        right: Vec<(String, Option<String>)>,
+    ) -> Option<Self> {
+        let right_by_key: HashMap<&str, &Option<String>> = right
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+            .collect();
+        let left_by_key: HashMap<&str, &Option<String>> = left
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+            .collect();
+
+        let mut only_left = Vec::new();
+        let mut different_values = Vec::new();
+        for (key, value) in &left {
+            match right_by_key.get(key.as_str()) {
+                None => only_left.push((key.clone(), value.clone())),
+                Some(&other) if other != value => different_values.push((
+                    key.clone(),
+                    value.clone().unwrap_or_default(),
+                    other.clone().unwrap_or_default(),
+                )),
+                Some(_) => {}
+            }
+        }
+        let only_right: Vec<_> = right
+            .iter()
+            .filter(|(key, _)| !left_by_key.contains_key(key.as_str()))
+            .cloned()
+            .collect();
+
+        if only_left.is_empty() && only_right.is_empty() && different_values.is_empty() {
+            None
+        } else {
+            Some(Self {
+                only_left,
+                only_right,
+                different_values,
+            })
+        }
+    }
+}
+
+impl WriteDiff for MapDiff {
+    fn write_diff(&self, buffer: &mut String) {
+        write!(buffer, "with map diff:").unwrap();
+        let entry = |key: &str, value: &Option<String>| match value {
+            Some(value) => format!("{}: {}", key, value),
+            None => key.to_string(),
+        };
+        if !self.only_left.is_empty() {
+            write!(buffer, "\n  only in left:").unwrap();
+            for (key, value) in &self.only_left {
+                write!(buffer, "\n    {}", Paint::red(&entry(key, value))).unwrap();
+            }
+        }
+        if !self.only_right.is_empty() {
+            write!(buffer, "\n  only in right:").unwrap();
+            for (key, value) in &self.only_right {
+                write!(buffer, "\n    {}", Paint::green(&entry(key, value))).unwrap();
+            }
+        }
+        if !self.different_values.is_empty() {
+            write!(buffer, "\n  different values:").unwrap();
+            for (key, left, right) in &self.different_values {
+                write!(
+                    buffer,
+                    "\n    {}: {} {} {}",
+                    key,
+                    Paint::red(left),
+                    Paint::blue("!=").bold(),
+                    Paint::green(right)
+                )
+                .unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Helpers for the checks on `left == right`, whose output shows where left and right differ
+//! for some well known kinds of values, instead of dumping both values in full.
+
+use std::fmt::Write;
+
+use yansi::Paint;
+
+use crate::assertions::print::CheckExpression;
+
+pub mod bytes;
+pub mod map;
+pub mod sequence;
+pub mod structure;
+pub mod text;
+
+/// A wrapper around the left and right hand side of a comparison, that helps us figure out what
+/// kind of values they are using autoref specialization, see [crate::assertions::maybe_copy::Wrap]
+pub struct Wrap<'a, Left: ?Sized, Right: ?Sized>(pub &'a Left, pub &'a Right);
+
+/// The traits that give the difference of the wrapped values for the different kinds of values.
+/// They all have the method `__assert2ify_diff`, which is implemented on a different number of
+/// references to [Wrap] for each kind, so calling it on `&&&&&&&&Wrap(left, right)` picks the
+/// first kind that the values belong to, in this order:
+/// 1. strings, see [text::IsString]
+/// 2. bytes, see [bytes::IsBytes]
+/// 3. values that dereference to bytes, see [bytes::IsDerefBytes]
+/// 4. sequences, see [sequence::IsSequence]
+/// 5. maps and sets with ordered keys, see [map::IsSortedMap]
+/// 6. maps and sets, see [map::IsMap]
+/// 7. values that implement Debug, see [structure::IsStructured]
+/// 8. all other values, which have no difference, see [IsNotDiffable]
+pub mod probes {
+    pub use super::bytes::{IsBytes, IsDerefBytes};
+    pub use super::map::{IsMap, IsSortedMap};
+    pub use super::sequence::IsSequence;
+    pub use super::structure::IsStructured;
+    pub use super::text::IsString;
+    pub use super::IsNotDiffable;
+}
+
+/// Fallback for values whose difference we can't show, see [probes]
+pub trait IsNotDiffable {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>>;
+}
+
+impl<Left: ?Sized, Right: ?Sized> IsNotDiffable for Wrap<'_, Left, Right> {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>> {
+        None
+    }
+}
+
+/// The difference between the left and right hand side of a failed comparison
+pub trait WriteDiff {
+    /// write the difference, starting with a line like `with diff:`
+    fn write_diff(&self, buffer: &mut String);
+}
+
+/// The expression of a failed check `left == right`, whose expansion is the difference
+/// between left and right
+pub struct DiffExpr<'a> {
+    pub diff: Box<dyn WriteDiff>,
+    /// the left hand side as it was written in the code
+    pub left_expr: &'a str,
+    /// the right hand side as it was written in the code
+    pub right_expr: &'a str,
+}
+
+impl CheckExpression for DiffExpr<'_> {
+    fn write_expression(&self, buffer: &mut String) {
+        write!(
+            buffer,
+            "{left} {op} {right}",
+            left = Paint::cyan(self.left_expr),
+            op = Paint::blue("==").bold(),
+            right = Paint::yellow(self.right_expr),
+        )
+        .unwrap();
+    }
+
+    fn write_expansion(&self, buffer: &mut String) {
+        self.diff.write_diff(buffer);
+    }
+}
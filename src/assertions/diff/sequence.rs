@@ -0,0 +1,169 @@
+//! The difference between two sequences like slices and Vecs, which shows the first index at which
+//! they differ instead of both sequences in full.
+
+use std::collections::VecDeque;
+use std::fmt::{Debug, Write};
+
+use yansi::Paint;
+
+use crate::assertions::diff::{Wrap, WriteDiff};
+
+/// the number of elements that are shown before and after the first difference
+const WINDOW_LENGTH: usize = 3;
+
+/// A sequence of elements that implement Debug, whose elements we can compare one by one
+pub trait Sequence {
+    /// the debug output of the elements of the sequence in order
+    fn debug_elements(&self) -> Vec<String>;
+}
+
+impl<T: Debug> Sequence for [T] {
+    fn debug_elements(&self) -> Vec<String> {
+        self.iter()
+            .map(|element| format!("{:?}", element))
+            .collect()
+    }
+}
+
+impl<T: Debug, const N: usize> Sequence for [T; N] {
+    fn debug_elements(&self) -> Vec<String> {
+        self.as_slice().debug_elements()
+    }
+}
+
+impl<T: Debug> Sequence for Vec<T> {
+    fn debug_elements(&self) -> Vec<String> {
+        self.as_slice().debug_elements()
+    }
+}
+
+impl<T: Debug> Sequence for VecDeque<T> {
+    fn debug_elements(&self) -> Vec<String> {
+        self.iter()
+            .map(|element| format!("{:?}", element))
+            .collect()
+    }
+}
+
+impl<S: Sequence + ?Sized> Sequence for &S {
+    fn debug_elements(&self) -> Vec<String> {
+        (**self).debug_elements()
+    }
+}
+
+impl<S: Sequence + ?Sized> Sequence for &mut S {
+    fn debug_elements(&self) -> Vec<String> {
+        (**self).debug_elements()
+    }
+}
+
+impl<S: Sequence + ?Sized> Sequence for Box<S> {
+    fn debug_elements(&self) -> Vec<String> {
+        (**self).debug_elements()
+    }
+}
+
+/// Gives the difference of the wrapped values if both are sequences of elements that implement Debug
+/// and their debug output differs, see [crate::assertions::diff::probes] for the order of the probes
+pub trait IsSequence {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>>;
+}
+
+impl<Left: Sequence + ?Sized, Right: Sequence + ?Sized> IsSequence for &&&&Wrap<'_, Left, Right> {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>> {
+        let diff = SequenceDiff::new(self.0.debug_elements(), self.1.debug_elements())?;
+        Some(Box::new(diff))
+    }
+}
+
+/// The difference between two sequences, i.e. the first index at which they differ and the debug
+/// output of the elements in a window around that index
+pub struct SequenceDiff {
+    /// the first index at which the elements differ or one of the sequences ended
+    index: usize,
+    left_length: usize,
+    right_length: usize,
+    /// the index of the first element in the window and the elements of the window, which are
+    /// None if the sequence has no element at that index
+    window_start: usize,
+    window: Vec<(Option<String>, Option<String>)>,
+}
+
+impl SequenceDiff {
+    /// The difference of the sequences with the given debug output of their elements.
+    /// # Returns
+    /// The difference or None if the debug output of the sequences is the same, which happens if
+    /// e.g. they contain NaNs. We can't point to the difference in this case.
+    fn new(left: Vec<String>, right: Vec<String>) -> Option<Self> {
+        if left == right {
+            return None;
+        }
+        let index = left
+            .iter()
+            .zip(&right)
+            .position(|(lhs, rhs)| lhs != rhs)
+            .unwrap_or_else(|| left.len().min(right.len()));
+        let window_start = index.saturating_sub(WINDOW_LENGTH);
+        let window_end = (index + WINDOW_LENGTH + 1).min(left.len().max(right.len()));
+        let window = (window_start..window_end)
+            .map(|i| (left.get(i).cloned(), right.get(i).cloned()))
+            .collect();
+        Some(Self {
+            index,
+            left_length: left.len(),
+            right_length: right.len(),
+            window_start,
+            window,
+        })
+    }
+}
+
+impl WriteDiff for SequenceDiff {
+    fn write_diff(&self, buffer: &mut String) {
+        writeln!(buffer, "with sequence diff:").unwrap();
+        write!(
+            buffer,
+            "  first difference at index {}",
+            Paint::blue(&self.index).bold()
+        )
+        .unwrap();
+        if self.left_length != self.right_length {
+            write!(
+                buffer,
+                "\n  lengths differ: left has {} elements, right has {} elements",
+                Paint::red(&self.left_length),
+                Paint::green(&self.right_length)
+            )
+            .unwrap();
+        }
+
+        let window_end = self.window_start + self.window.len();
+        let index_width = window_end.saturating_sub(1).to_string().len();
+        if self.window_start > 0 {
+            write!(buffer, "\n  ...").unwrap();
+        }
+        for (offset, (left, right)) in self.window.iter().enumerate() {
+            let index = self.window_start + offset;
+            let differs = left != right;
+            let missing = || "(no element)".to_string();
+            let left = left.clone().unwrap_or_else(missing);
+            let right = right.clone().unwrap_or_else(missing);
+            write!(buffer, "\n  [{:>width$}] ", index, width = index_width).unwrap();
+            if differs {
+                write!(
+                    buffer,
+                    "{} {} {}",
+                    Paint::red(&left),
+                    Paint::blue("!=").bold(),
+                    Paint::green(&right)
+                )
+                .unwrap();
+            } else {
+                write!(buffer, "{} {} {}", left, Paint::blue("==").bold(), right).unwrap();
+            }
+        }
+        if window_end < self.left_length.max(self.right_length) {
+            write!(buffer, "\n  ...").unwrap();
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! The difference between two values like structs and enums, which shows only the fields that
+//! differ together with their path (like `server.tls.cert_path`) instead of both values in full.
+//! The difference is calculated from the pretty debug output of the values, so any type that
+//! implements Debug works.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Write};
+
+use yansi::Paint;
+
+use crate::assertions::diff::{Wrap, WriteDiff};
+use crate::assertions::print::options::is_compact_good;
+
+/// Gives the difference of the wrapped values if both implement Debug and their compact debug
+/// output is too long to print it as is (like assert2 does for `{:?}` output that is longer than a
+/// line), see [crate::assertions::diff::probes] for the order of the probes
+pub trait IsStructured {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>>;
+}
+
+impl<Left: Debug + ?Sized, Right: Debug + ?Sized> IsStructured for &Wrap<'_, Left, Right> {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>> {
+        // small values like `Some(1)` or `(1, "a")` are printed in a single line anyway
+        if is_compact_good(&[format!("{:?}", self.0), format!("{:?}", self.1)]) {
+            return None;
+        }
+        let diff = StructuralDiff::new(&format!("{:#?}", self.0), &format!("{:#?}", self.1))?;
+        Some(Box::new(diff))
+    }
+}
+
+/// A field (or element) of a value in the pretty debug output
+struct Field {
+    /// the path of the field, like `server.tls.cert_path` or `ports[1]`
+    path: String,
+    /// the first line of the debug output of a nested value (like `Server {`) or None
+    /// if the field is not nested
+    header: Option<String>,
+    /// the compact debug output of the field
+    value: String,
+}
+
+/// A field that differs between the left and right value. The debug output of a field is None
+/// if one of the values does not have the field.
+struct Difference {
+    path: String,
+    left: Option<String>,
+    right: Option<String>,
+}
+
+/// The difference between two values, i.e. the fields that differ
+pub struct StructuralDiff {
+    differences: Vec<Difference>,
+}
+
+impl StructuralDiff {
+    /// The difference between the values with the given pretty debug output.
+    /// # Returns
+    /// The difference or None if both values fit in a single line (there is nothing to gain from
+    /// a structural diff for those) or we can't find the difference in the debug output.
+    fn new(left: &str, right: &str) -> Option<Self> {
+        if !left.contains('\n') && !right.contains('\n') {
+            return None;
+        }
+        let left_fields = fields(left);
+        let right_fields = fields(right);
+        let left_index_by_path: HashMap<&str, usize> = left_fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| (field.path.as_str(), index))
+            .collect();
+        let right_by_path: HashMap<&str, &Field> = right_fields
+            .iter()
+            .map(|field| (field.path.as_str(), field))
+            .collect();
+
+        // the differences with the position in the left value where they belong, so we can put them
+        // in order of appearance
+        let mut differences: Vec<((usize, usize), Difference)> = Vec::new();
+        // the paths of nested values that are reported as a whole, so we skip their fields
+        let mut reported: Vec<&str> = Vec::new();
+        let is_reported =
+            |reported: &[&str], path: &str| reported.iter().any(|parent| is_inside(path, parent));
+
+        for (index, field) in left_fields.iter().enumerate() {
+            if is_reported(&reported, &field.path) {
+                continue;
+            }
+            match right_by_path.get(field.path.as_str()) {
+                Some(other) if field.header.is_some() && field.header == other.header => {}
+                Some(other) if field.value == other.value => {}
+                other => {
+                    if field.header.is_some() || matches!(other, Some(o) if o.header.is_some()) {
+                        reported.push(&field.path);
+                    }
+                    let difference = Difference {
+                        path: field.path.clone(),
+                        left: Some(field.value.clone()),
+                        right: other.map(|other| other.value.clone()),
+                    };
+                    differences.push(((index, 0), difference));
+                }
+            }
+        }
+        // fields that only the right value has belong after the last field that both values have
+        let mut previous_left_index = 0;
+        for (index, field) in right_fields.iter().enumerate() {
+            if let Some(&left_index) = left_index_by_path.get(field.path.as_str()) {
+                previous_left_index = left_index;
+            } else if !is_reported(&reported, &field.path) {
+                if field.header.is_some() {
+                    reported.push(&field.path);
+                }
+                let difference = Difference {
+                    path: field.path.clone(),
+                    left: None,
+                    right: Some(field.value.clone()),
+                };
+                differences.push(((previous_left_index, index + 1), difference));
+            }
+        }
+        differences.sort_by_key(|(position, _)| *position);
+        let differences: Vec<Difference> = differences
+            .into_iter()
+            .map(|(_, difference)| difference)
+            .collect();
+
+        // if the values differ as a whole, the output of assert2 is just as good
+        let differ_as_a_whole = differences
+            .iter()
+            .any(|difference| difference.path.is_empty());
+        if differences.is_empty() || differ_as_a_whole {
+            None
+        } else {
+            Some(Self { differences })
+        }
+    }
+}
+
+/// whether the path lies inside the nested value with the given parent path
+fn is_inside(path: &str, parent: &str) -> bool {
+    path.len() > parent.len()
+        && path.starts_with(parent)
+        && (parent.is_empty() || path[parent.len()..].starts_with(['.', '[']))
+}
+
+/// The kind of a nested value, which tells us how the paths of its fields look like
+#[derive(Clone, Copy)]
+enum Nesting {
+    /// `Name { field: value }`, whose fields have the path `parent.field`
+    Struct,
+    /// `Name(value)` or `(value, value)`, whose fields have the path `parent.0`
+    Tuple,
+    /// `[value]`, whose elements have the path `parent[0]`
+    List,
+    /// `{key: value}` or `{value}` for sets, whose elements have the path `parent[key]` or `parent[0]`
+    Map,
+}
+
+/// all fields and nested fields in the given pretty debug output of a value, in order of appearance
+fn fields(debug: &str) -> Vec<Field> {
+    let lines: Vec<&str> = debug.lines().map(str::trim).collect();
+    let mut fields: Vec<Field> = Vec::new();
+    // the nesting of the currently open values, with the index of their field, the index of their
+    // first line and the number of their fields so far
+    let mut open: Vec<(Nesting, usize, usize, usize)> = Vec::new();
+
+    for (line_index, line) in lines.iter().enumerate() {
+        if line.starts_with(['}', ']', ')']) {
+            if let Some((_, field_index, first_line, _)) = open.pop() {
+                let mut value_lines = vec![value_of(lines[first_line], &open)];
+                value_lines.extend_from_slice(&lines[first_line + 1..=line_index]);
+                fields[field_index].value = compact(&value_lines);
+            }
+            continue;
+        }
+        let line = line.strip_suffix(',').unwrap_or(line);
+        let (path, value) = match open.last_mut() {
+            None => (String::new(), line),
+            Some((nesting, parent_index, _, count)) => {
+                let parent = &fields[*parent_index].path;
+                let index = *count;
+                *count += 1;
+                match (*nesting, split_key(line)) {
+                    (Nesting::Struct, Some((key, value))) => (join(parent, ".", key), value),
+                    (Nesting::Map, Some((key, value))) => (format!("{}[{}]", parent, key), value),
+                    (Nesting::Tuple, _) => (join(parent, ".", &index.to_string()), line),
+                    _ => (format!("{}[{}]", parent, index), line),
+                }
+            }
+        };
+        let nesting = if value == "{" {
+            Some(Nesting::Map)
+        } else if value.ends_with('{') {
+            Some(Nesting::Struct)
+        } else if value.ends_with('(') {
+            Some(Nesting::Tuple)
+        } else if value.ends_with('[') {
+            Some(Nesting::List)
+        } else {
+            None
+        };
+        if let Some(nesting) = nesting {
+            open.push((nesting, fields.len(), line_index, 0));
+        }
+        fields.push(Field {
+            path,
+            header: nesting.map(|_| value.to_string()),
+            value: value.to_string(),
+        });
+    }
+    fields
+}
+
+/// the value in the given line without its key, if the line is inside of the open values
+fn value_of<'a>(line: &'a str, open: &[(Nesting, usize, usize, usize)]) -> &'a str {
+    match open.last() {
+        Some((Nesting::Struct, ..)) | Some((Nesting::Map, ..)) => {
+            split_key(line).map_or(line, |(_, value)| value)
+        }
+        _ => line,
+    }
+}
+
+/// join the parent path and the key with the given separator, unless the parent is the root
+fn join(parent: &str, separator: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}{}{}", parent, separator, key)
+    }
+}
+
+/// split a line like `key: value` into its key and value. The key might be a string containing
+/// `: ` itself, if it is the key of a map.
+fn split_key(line: &str) -> Option<(&str, &str)> {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, character) in line.char_indices() {
+        match character {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            ':' if !in_string && line[index..].starts_with(": ") => {
+                return Some((&line[..index], &line[index + 2..]));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// turn the lines of the pretty debug output of a value back into its compact debug output,
+/// e.g. `["Tls {", "cert_path: \"a\",", "}"]` becomes `Tls { cert_path: "a" }`
+fn compact(lines: &[&str]) -> String {
+    let mut compact = String::new();
+    for line in lines {
+        if line.starts_with(['}', ']', ')']) {
+            let without_comma = compact.trim_end_matches(',').len();
+            compact.truncate(without_comma);
+            if line.starts_with('}') {
+                compact.push(' ');
+            }
+        } else if !compact.is_empty() && !compact.ends_with(['[', '(']) {
+            compact.push(' ');
+        }
+        compact.push_str(line);
+    }
+    compact.trim_end_matches(',').to_string()
+}
+
+impl WriteDiff for StructuralDiff {
+    fn write_diff(&self, buffer: &mut String) {
+        write!(buffer, "with structural diff:").unwrap();
+        let missing = || String::from("(missing)");
+        for difference in &self.differences {
+            write!(
+                buffer,
+                "\n  {}: {} {} {}",
+                Paint::new(&difference.path).bold(),
+                Paint::red(&difference.left.clone().unwrap_or_else(missing)),
+                Paint::blue("!=").bold(),
+                Paint::green(&difference.right.clone().unwrap_or_else(missing)),
+            )
+            .unwrap();
+        }
+    }
+}
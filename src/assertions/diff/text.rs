@@ -0,0 +1,227 @@
+//! The difference between two multi-line strings as a unified, line based diff, which shows
+//! otherwise invisible differences like whitespace and line endings.
+
+use std::fmt::Write;
+
+use yansi::{Paint, Style};
+
+use crate::assertions::diff::{Wrap, WriteDiff};
+
+/// the number of unchanged lines that are shown before and after changed lines
+const CONTEXT_LENGTH: usize = 3;
+
+/// Gives the difference of the wrapped values if both are strings and at least one of them has
+/// more than one line, see [crate::assertions::diff::probes] for the order of the probes
+pub trait IsString {
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>>;
+}
+
+impl<Left, Right> IsString for &&&&&&&Wrap<'_, Left, Right>
+where
+    Left: AsRef<str> + ?Sized,
+    Right: AsRef<str> + ?Sized,
+{
+    fn __assert2ify_diff(&self) -> Option<Box<dyn WriteDiff>> {
+        let diff = TextDiff::new(self.0.as_ref(), self.1.as_ref())?;
+        Some(Box::new(diff))
+    }
+}
+
+/// A line of the diff
+enum Line {
+    /// a line that only the left string has, with its line number
+    Removed(usize, String),
+    /// a line that only the right string has, with its line number
+    Added(usize, String),
+    /// a line that both strings have, with the line numbers in the left and the right string
+    Unchanged(usize, usize, String),
+}
+
+/// The line based difference between two strings, which consists of hunks of changed lines
+/// together with the unchanged lines around them, just like a unified diff
+pub struct TextDiff {
+    hunks: Vec<Vec<Line>>,
+}
+
+impl TextDiff {
+    /// The difference between the given strings.
+    /// # Returns
+    /// The difference or None if both strings consist of a single line. The output of assert2 is
+    /// already good for those.
+    fn new(left: &str, right: &str) -> Option<Self> {
+        if !left.contains('\n') && !right.contains('\n') {
+            return None;
+        }
+        // the lines keep their line endings, so that different line endings are differences, too
+        let left_lines: Vec<&str> = left.split_inclusive('\n').collect();
+        let right_lines: Vec<&str> = right.split_inclusive('\n').collect();
+
+        let (mut left_number, mut right_number) = (0, 0);
+        let mut lines = Vec::new();
+        for result in ::diff::slice(&left_lines, &right_lines) {
+            match result {
+                ::diff::Result::Left(line) => {
+                    left_number += 1;
+                    lines.push(Line::Removed(left_number, line.to_string()));
+                }
+                ::diff::Result::Right(line) => {
+                    right_number += 1;
+                    lines.push(Line::Added(right_number, line.to_string()));
+                }
+                ::diff::Result::Both(line, _) => {
+                    left_number += 1;
+                    right_number += 1;
+                    lines.push(Line::Unchanged(left_number, right_number, line.to_string()));
+                }
+            }
+        }
+        Some(Self {
+            hunks: hunks(lines),
+        })
+    }
+}
+
+/// split the lines into hunks of changed lines with at most [CONTEXT_LENGTH] unchanged lines
+/// before and after them. Unchanged lines that are further away from any changes are dropped.
+fn hunks(lines: Vec<Line>) -> Vec<Vec<Line>> {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Line::Unchanged(..)))
+        .map(|(index, _)| index)
+        .collect();
+    let is_shown = |index: usize| {
+        changed
+            .iter()
+            .any(|&change| index + CONTEXT_LENGTH >= change && index <= change + CONTEXT_LENGTH)
+    };
+
+    let mut hunks: Vec<Vec<Line>> = Vec::new();
+    let mut previous_shown = false;
+    for (index, line) in lines.into_iter().enumerate() {
+        let shown = is_shown(index);
+        if shown {
+            match hunks.last_mut() {
+                Some(hunk) if previous_shown => hunk.push(line),
+                _ => hunks.push(vec![line]),
+            }
+        }
+        previous_shown = shown;
+    }
+    hunks
+}
+
+impl WriteDiff for TextDiff {
+    fn write_diff(&self, buffer: &mut String) {
+        write!(buffer, "with diff:").unwrap();
+        for hunk in &self.hunks {
+            write_hunk_header(buffer, hunk);
+            for line in hunk {
+                match line {
+                    Line::Removed(_, text) => {
+                        write_changed_line(buffer, '-', text, Style::new().red())
+                    }
+                    Line::Added(_, text) => {
+                        write_changed_line(buffer, '+', text, Style::new().green())
+                    }
+                    Line::Unchanged(_, _, text) => {
+                        let text = text.strip_suffix('\n').unwrap_or(text);
+                        write!(buffer, "\n   {}", text.dim()).unwrap()
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// write the header of a hunk like `@@ -1,4 +1,5 @@`, with the first line numbers and the number
+/// of lines in the left and right string
+fn write_hunk_header(buffer: &mut String, hunk: &[Line]) {
+    let left_numbers = hunk.iter().filter_map(|line| match line {
+        Line::Removed(number, _) | Line::Unchanged(number, _, _) => Some(*number),
+        Line::Added(..) => None,
+    });
+    let right_numbers = hunk.iter().filter_map(|line| match line {
+        Line::Added(number, _) | Line::Unchanged(_, number, _) => Some(*number),
+        Line::Removed(..) => None,
+    });
+    let range = |numbers: Vec<usize>| match numbers.first() {
+        Some(first) => format!("{},{}", first, numbers.len()),
+        None => String::from("0,0"),
+    };
+    let header = format!(
+        "@@ -{} +{} @@",
+        range(left_numbers.collect()),
+        range(right_numbers.collect())
+    );
+    write!(buffer, "\n  {}", header.cyan()).unwrap();
+}
+
+/// write a removed or added line with the given marker (`-` or `+`) in the given style.
+/// Whitespace at the end of the line, tabs, line endings and invisible characters are made visible.
+fn write_changed_line(buffer: &mut String, marker: char, text: &str, style: Style) {
+    let (content, has_newline) = match text.strip_suffix('\n') {
+        Some(content) => (content, true),
+        None => (text, false),
+    };
+    // whitespace before a `\r\n` line ending is trailing whitespace, too
+    let trailing_start = content
+        .strip_suffix('\r')
+        .unwrap_or(content)
+        .trim_end_matches([' ', '\t'])
+        .len();
+    let marker_style = style.bold().underline();
+
+    write!(buffer, "\n  {} ", marker.paint(style)).unwrap();
+    // the characters are written in runs, so we don't have to style every character on its own
+    let mut run = String::new();
+    for (index, character) in content.char_indices() {
+        let visible_marker = match character {
+            ' ' if index >= trailing_start => Some(String::from("·")),
+            '\t' => Some(String::from("→")),
+            '\r' => Some(String::from("␍")),
+            character if is_invisible(character) => Some(format!("\\u{{{:x}}}", character as u32)),
+            _ => None,
+        };
+        match visible_marker {
+            Some(visible_marker) => {
+                if !run.is_empty() {
+                    write!(buffer, "{}", std::mem::take(&mut run).paint(style)).unwrap();
+                }
+                write!(buffer, "{}", visible_marker.paint(marker_style)).unwrap();
+            }
+            None => run.push(character),
+        }
+    }
+    if !run.is_empty() {
+        write!(buffer, "{}", run.paint(style)).unwrap();
+    }
+    if !has_newline {
+        write!(buffer, " {}", "(no newline at end)".dim()).unwrap();
+    }
+}
+
+/// whether the character is invisible or easily mistaken for a space, like a non-breaking space,
+/// a zero width space or a byte order mark
+fn is_invisible(character: char) -> bool {
+    (character.is_whitespace() && character != ' ')
+        || character.is_control()
+        || matches!(
+            character,
+            '\u{00ad}'
+                | '\u{034f}'
+                | '\u{061c}'
+                | '\u{115f}'
+                | '\u{1160}'
+                | '\u{17b4}'
+                | '\u{17b5}'
+                | '\u{180e}'
+                | '\u{200b}'..='\u{200f}'
+                | '\u{202a}'..='\u{202e}'
+                | '\u{2060}'..='\u{206f}'
+                | '\u{3164}'
+                | '\u{fe00}'..='\u{fe0f}'
+                | '\u{feff}'
+                | '\u{ffa0}'
+        )
+}
@@ -0,0 +1,205 @@
+//! Helpers for the checks on `iterator.all(predicate)` and `iterator.any(predicate)`, whose output
+//! shows the elements that made the check fail.
+
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Write};
+use std::marker::PhantomData;
+
+use yansi::Paint;
+
+use crate::assertions::print::CheckExpression;
+
+/// A wrapper that helps us figure out whether the receiver of `receiver.all(predicate)` or
+/// `receiver.any(predicate)` is an iterator, see [crate::assertions::maybe_copy::Wrap]. Other types
+/// with methods of the same names are checked like any other boolean expression.
+pub struct Wrap<'a, T>(pub &'a T);
+
+/// Gives the [IteratorTag] if the wrapped type is an iterator
+pub trait IsIterator<T: Iterator> {
+    fn __assert2ify_iterator_tag(&self) -> IteratorTag<T> {
+        IteratorTag(PhantomData)
+    }
+}
+
+/// Fallback for types that are not iterators, see [IsIterator]
+pub trait IsNotIterator {
+    fn __assert2ify_iterator_tag(&self) -> NotIteratorTag {
+        NotIteratorTag
+    }
+}
+
+impl<T: Iterator> IsIterator<T> for &Wrap<'_, T> {}
+
+impl<T> IsNotIterator for Wrap<'_, T> {}
+
+/// the result of [IsIterator] for an iterator of the type `I`
+pub struct IteratorTag<I>(PhantomData<I>);
+
+/// the result of [IsNotIterator]
+pub struct NotIteratorTag;
+
+impl<I: Iterator> IteratorTag<I> {
+    /// whether the receiver is an iterator, whose elements are recorded
+    pub fn is_iterator(&self) -> bool {
+        true
+    }
+
+    /// the record of the elements, which keeps copies of them made by the given function
+    /// (which gives None for elements that are not Copy). If `keep_all` is false, only the
+    /// copy of the last element is kept.
+    pub fn record(&self, copy: fn(&I::Item) -> Option<I::Item>, keep_all: bool) -> Record<I::Item> {
+        Record {
+            count: Cell::new(0),
+            copies: RefCell::new(Some(Vec::new())),
+            copy,
+            keep_all,
+        }
+    }
+
+    /// the iterator, which adds the elements it gives to the record
+    pub fn prepare<'a>(&self, iterator: I, record: &'a Record<I::Item>) -> Recording<'a, I> {
+        Recording { iterator, record }
+    }
+}
+
+impl NotIteratorTag {
+    /// whether the receiver is an iterator, whose elements are recorded
+    pub fn is_iterator(&self) -> bool {
+        false
+    }
+
+    /// an empty record, since there are no elements to record
+    pub fn record(&self, copy: fn(&()) -> Option<()>, keep_all: bool) -> Record<()> {
+        Record {
+            count: Cell::new(0),
+            copies: RefCell::new(None),
+            copy,
+            keep_all,
+        }
+    }
+
+    /// the value itself, whose own method is called
+    pub fn prepare<T>(&self, value: T, _record: &Record<()>) -> T {
+        value
+    }
+}
+
+/// The number of elements that an iterator gave and the copies of them, if they are Copy
+pub struct Record<Element> {
+    count: Cell<usize>,
+    copies: RefCell<Option<Vec<Element>>>,
+    copy: fn(&Element) -> Option<Element>,
+    keep_all: bool,
+}
+
+impl<Element> Record<Element> {
+    fn push(&self, element: &Element) {
+        self.count.set(self.count.get() + 1);
+        let mut copies = self.copies.borrow_mut();
+        match (copies.as_mut(), (self.copy)(element)) {
+            (Some(copies), Some(copy)) => {
+                if !self.keep_all {
+                    copies.clear();
+                }
+                copies.push(copy);
+            }
+            _ => *copies = None,
+        }
+    }
+
+    /// the number of elements and their copies, which are None if they are not Copy
+    pub fn into_parts(self) -> (usize, Option<Vec<Element>>) {
+        (self.count.get(), self.copies.into_inner())
+    }
+}
+
+/// An iterator that adds the elements it gives to a [Record]
+pub struct Recording<'a, I: Iterator> {
+    iterator: I,
+    record: &'a Record<I::Item>,
+}
+
+impl<I: Iterator> Iterator for Recording<'_, I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let element = self.iterator.next()?;
+        self.record.push(&element);
+        Some(element)
+    }
+}
+
+/// The expression of a failed check on `iterator.all(predicate)`
+pub struct AllExpr<'a, Element> {
+    /// the index of the first element for which the predicate was false
+    pub index: usize,
+    /// the first element for which the predicate was false, which is None if it was moved into
+    /// the predicate
+    pub element: Option<&'a Element>,
+    /// the iterator as it was written in the code
+    pub iterator_expr: &'a str,
+    /// the predicate as it was written in the code
+    pub predicate_expr: &'a str,
+}
+
+/// The expression of a failed check on `iterator.any(predicate)`
+pub struct AnyExpr<'a, Element> {
+    /// the number of elements, none of which satisfied the predicate
+    pub count: usize,
+    /// all elements of the iterator, which is None if they were moved into the predicate
+    pub elements: Option<&'a [Element]>,
+    /// the iterator as it was written in the code
+    pub iterator_expr: &'a str,
+    /// the predicate as it was written in the code
+    pub predicate_expr: &'a str,
+}
+
+impl<Element: Debug> CheckExpression for AllExpr<'_, Element> {
+    fn write_expression(&self, buffer: &mut String) {
+        write_method_call(buffer, self.iterator_expr, "all", self.predicate_expr);
+    }
+
+    fn write_expansion(&self, buffer: &mut String) {
+        writeln!(buffer, "with expansion:").unwrap();
+        write!(
+            buffer,
+            "  the element at index {} does not satisfy the predicate",
+            Paint::cyan(&self.index)
+        )
+        .unwrap();
+        if let Some(element) = self.element {
+            write!(buffer, ":\n  {:?}", element.yellow()).unwrap();
+        }
+    }
+}
+
+impl<Element: Debug> CheckExpression for AnyExpr<'_, Element> {
+    fn write_expression(&self, buffer: &mut String) {
+        write_method_call(buffer, self.iterator_expr, "any", self.predicate_expr);
+    }
+
+    fn write_expansion(&self, buffer: &mut String) {
+        writeln!(buffer, "with expansion:").unwrap();
+        write!(
+            buffer,
+            "  none of the {} elements satisfies the predicate",
+            Paint::cyan(&self.count)
+        )
+        .unwrap();
+        if let Some(elements) = self.elements {
+            write!(buffer, ":\n  {:?}", elements.yellow()).unwrap();
+        }
+    }
+}
+
+/// write the expression `iterator.method(predicate)` in the colors of assert2
+fn write_method_call(buffer: &mut String, iterator: &str, method: &str, predicate: &str) {
+    write!(
+        buffer,
+        "{iterator}.{method}({predicate})",
+        iterator = Paint::cyan(iterator),
+        method = Paint::blue(method).bold(),
+        predicate = Paint::yellow(predicate),
+    )
+    .unwrap();
+}
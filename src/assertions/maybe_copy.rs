@@ -0,0 +1,28 @@
+//! Autoref specialization to get a copy of a value if its type is Copy. This is e.g. used to
+//! print the argument of a method call, which was moved into the call.
+
+/// A wrapper that helps us figure out properties of the wrapped type using autoref specialization,
+/// just like assert2 does to figure out whether a type implements Debug
+pub struct Wrap<'a, T: ?Sized>(pub &'a T);
+
+/// Gives a copy of the wrapped value if it is Copy
+pub trait IsCopy<T> {
+    fn __assert2ify_copy(&self) -> Option<T>;
+}
+
+/// Fallback for values that are not Copy, see [IsCopy]
+pub trait IsNotCopy<T> {
+    fn __assert2ify_copy(&self) -> Option<T>;
+}
+
+impl<T: Copy> IsCopy<T> for &Wrap<'_, T> {
+    fn __assert2ify_copy(&self) -> Option<T> {
+        Some(*self.0)
+    }
+}
+
+impl<T> IsNotCopy<T> for Wrap<'_, T> {
+    fn __assert2ify_copy(&self) -> Option<T> {
+        None
+    }
+}
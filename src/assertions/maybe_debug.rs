@@ -0,0 +1,59 @@
+// Adapted from the `__assert2_impl::maybe_debug` module of assert2 0.3.16 (https://github.com/de-vri-es/assert2-rs).
+// Copyright (c) 2020-2024, Maarten de Vries <maarten@de-vri.es>
+// Licensed under the BSD 2-Clause License, see the LICENSE file for its conditions and disclaimer.
+
+//! Autoref specialization to print the debug output of a value if its type implements Debug and
+//! a placeholder with the name of its type otherwise, just like assert2 does.
+
+use std::any::type_name;
+use std::fmt::{self, Debug, Formatter};
+
+/// A wrapper that helps us figure out whether the wrapped type implements Debug,
+/// see [crate::assertions::maybe_copy::Wrap]
+pub struct Wrap<'a, T: ?Sized>(pub &'a T);
+
+/// Gives the wrapped value itself if it implements Debug
+pub trait IsDebug {
+    fn __assert2ify_maybe_debug(&self) -> DebugTag {
+        DebugTag
+    }
+}
+
+/// Fallback for values that don't implement Debug, see [IsDebug]
+pub trait IsNotDebug {
+    fn __assert2ify_maybe_debug(&self) -> NotDebugTag {
+        NotDebugTag
+    }
+}
+
+impl<T: Debug + ?Sized> IsDebug for &Wrap<'_, T> {}
+
+impl<T: ?Sized> IsNotDebug for Wrap<'_, T> {}
+
+/// the result of [IsDebug], which wraps a value that implements Debug
+pub struct DebugTag;
+
+/// the result of [IsNotDebug], which wraps a value that doesn't implement Debug
+pub struct NotDebugTag;
+
+impl DebugTag {
+    pub fn wrap<T: ?Sized>(self, value: &T) -> &T {
+        value
+    }
+}
+
+impl NotDebugTag {
+    pub fn wrap<T: ?Sized>(self, value: &T) -> NotDebug<'_, T> {
+        NotDebug(value)
+    }
+}
+
+/// A value whose type doesn't implement Debug. Its debug output is a placeholder with the name of
+/// its type.
+pub struct NotDebug<'a, T: ?Sized>(&'a T);
+
+impl<T: ?Sized> Debug for NotDebug<'_, T> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "<object of type {}>", type_name::<T>())
+    }
+}
@@ -0,0 +1,192 @@
+//! Helpers for the checks on method calls of the form `receiver.method(argument)`, like
+//! `receiver.contains(argument)`, whose output shows the values of the receiver and the argument.
+
+use std::borrow::Cow;
+use std::fmt::{Debug, Write};
+
+use yansi::Paint;
+
+use crate::assertions::maybe_copy::Wrap;
+use crate::assertions::print::CheckExpression;
+
+/// strings with more characters than this get a hint at the closest partial match
+const LONG_STRING_LENGTH: usize = 32;
+/// the number of characters that are shown around the closest partial match
+const EXCERPT_CONTEXT_LENGTH: usize = 24;
+
+/// Gives the wrapped value as a string, if it is a string
+pub trait IsText<'a> {
+    fn __assert2ify_text(&self) -> Option<Cow<'a, str>>;
+}
+
+/// Gives the wrapped value as a string, if it is a char
+pub trait IsChar<'a> {
+    fn __assert2ify_text(&self) -> Option<Cow<'a, str>>;
+}
+
+/// Fallback for everything that is not text, see [IsText]
+pub trait IsNotText<'a> {
+    fn __assert2ify_text(&self) -> Option<Cow<'a, str>>;
+}
+
+impl<'a, T: AsRef<str> + ?Sized> IsText<'a> for &&Wrap<'a, T> {
+    fn __assert2ify_text(&self) -> Option<Cow<'a, str>> {
+        Some(Cow::Borrowed(self.0.as_ref()))
+    }
+}
+
+impl<'a> IsChar<'a> for &Wrap<'a, char> {
+    fn __assert2ify_text(&self) -> Option<Cow<'a, str>> {
+        Some(Cow::Owned(self.0.to_string()))
+    }
+}
+
+impl<'a, T: ?Sized> IsNotText<'a> for Wrap<'a, T> {
+    fn __assert2ify_text(&self) -> Option<Cow<'a, str>> {
+        None
+    }
+}
+
+/// The expression of a failed check on a method call `receiver.method(argument)`.
+pub struct MethodCallExpr<'a, Receiver, Argument> {
+    /// the value of the receiver
+    pub receiver: &'a Receiver,
+    /// the value of the argument, which is None if it was moved into the method call
+    pub argument: Option<&'a Argument>,
+    /// the receiver as a string, if it is one
+    pub receiver_text: Option<&'a str>,
+    /// the argument as a string, if it is one (or a char)
+    pub argument_text: Option<&'a str>,
+    /// the name of the method
+    pub method: &'a str,
+    /// the receiver as it was written in the code
+    pub receiver_expr: &'a str,
+    /// the argument as it was written in the code
+    pub argument_expr: &'a str,
+}
+
+impl<Receiver: Debug, Argument: Debug> CheckExpression for MethodCallExpr<'_, Receiver, Argument> {
+    fn write_expression(&self, buffer: &mut String) {
+        write!(
+            buffer,
+            "{receiver}.{method}({argument})",
+            receiver = Paint::cyan(self.receiver_expr),
+            method = Paint::blue(self.method).bold(),
+            argument = Paint::yellow(self.argument_expr),
+        )
+        .unwrap();
+    }
+
+    fn write_expansion(&self, buffer: &mut String) {
+        writeln!(buffer, "with expansion:").unwrap();
+        write!(
+            buffer,
+            "  {receiver:?}.{method}(",
+            receiver = self.receiver.cyan(),
+            method = Paint::blue(self.method).bold(),
+        )
+        .unwrap();
+        match self.argument {
+            Some(argument) => write!(buffer, "{:?})", argument.yellow()).unwrap(),
+            // we can't print the value, because it was moved into the method call
+            None => write!(buffer, "{})", Paint::yellow(self.argument_expr)).unwrap(),
+        }
+
+        if let (Some(haystack), Some(needle)) = (self.receiver_text, self.argument_text) {
+            if haystack.chars().count() > LONG_STRING_LENGTH {
+                if let Some((start, length)) = closest_partial_match(self.method, haystack, needle)
+                {
+                    write!(buffer, "\nclosest partial match at byte {}:\n", start).unwrap();
+                    write_excerpt(buffer, haystack, start, length);
+                }
+            }
+        }
+    }
+}
+
+/// Find the part of the haystack that comes closest to satisfying the method with the needle.
+/// * `contains`: the longest prefix of the needle that is contained in the haystack.
+/// * `starts_with`: the longest common prefix of haystack and needle.
+/// * `ends_with`: the longest common suffix of haystack and needle.
+/// # Returns
+/// The start and length (both in bytes) of the partial match inside the haystack or None if there is
+/// no partial match (or we don't know how to find one for the method).
+fn closest_partial_match(method: &str, haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    match method {
+        "contains" => needle
+            .char_indices()
+            .map(|(index, character)| index + character.len_utf8())
+            .rev()
+            .find_map(|length| {
+                haystack
+                    .find(&needle[..length])
+                    .map(|start| (start, length))
+            }),
+        "starts_with" => {
+            let length = haystack
+                .chars()
+                .zip(needle.chars())
+                .take_while(|(lhs, rhs)| lhs == rhs)
+                .map(|(character, _)| character.len_utf8())
+                .sum();
+            Some((0, length)).filter(|&(_, length)| length > 0)
+        }
+        "ends_with" => {
+            let length: usize = haystack
+                .chars()
+                .rev()
+                .zip(needle.chars().rev())
+                .take_while(|(lhs, rhs)| lhs == rhs)
+                .map(|(character, _)| character.len_utf8())
+                .sum();
+            Some((haystack.len() - length, length)).filter(|&(_, length)| length > 0)
+        }
+        _ => None,
+    }
+}
+
+/// write an excerpt of the text around the given range of bytes and mark the range with carets
+fn write_excerpt(buffer: &mut String, text: &str, start: usize, length: usize) {
+    let excerpt_start = text[..start]
+        .char_indices()
+        .rev()
+        .nth(EXCERPT_CONTEXT_LENGTH - 1)
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+    let excerpt_end = text[start + length..]
+        .char_indices()
+        .nth(EXCERPT_CONTEXT_LENGTH)
+        .map(|(index, _)| start + length + index)
+        .unwrap_or(text.len());
+
+    // the characters are escaped, so that e.g. line breaks don't mess up the output
+    let escape = |text: &str| text.escape_debug().to_string();
+    let ellipsis = |condition: bool| if condition { "..." } else { "" };
+    let before = format!(
+        "{}{}",
+        ellipsis(excerpt_start > 0),
+        escape(&text[excerpt_start..start])
+    );
+    let partial_match = escape(&text[start..start + length]);
+    let after = format!(
+        "{}{}",
+        escape(&text[start + length..excerpt_end]),
+        ellipsis(excerpt_end < text.len())
+    );
+
+    writeln!(
+        buffer,
+        "  {}{}{}",
+        before,
+        Paint::green(&partial_match).bold(),
+        after
+    )
+    .unwrap();
+    write!(
+        buffer,
+        "  {}{}",
+        " ".repeat(before.chars().count()),
+        Paint::green(&"^".repeat(partial_match.chars().count())).bold()
+    )
+    .unwrap();
+}
@@ -0,0 +1,882 @@
+/// the debug variant of the `check!` macro, see the documentation there
+pub use crate::__debug_check as debug_check;
+/// reexport of the `assert!` macro of the assert2 crate to make this crate self-contained
+pub use assert2::assert;
+/// reexport of the `check!` macro of the assert2 crate to make this crate self-contained
+pub use assert2::check;
+/// reexport of the `debug_assert!` macro of the assert2 crate to make this crate self-contained
+pub use assert2::debug_assert;
+/// reexport of the `let_assert!` macro of the assert2 crate to make this crate self-contained
+pub use assert2::let_assert;
+
+pub mod approx;
+pub mod collection;
+pub mod diff;
+pub mod iterator;
+pub mod maybe_copy;
+pub mod maybe_debug;
+pub mod method_call;
+pub mod pattern;
+pub mod print;
+pub mod unwrap;
+
+#[macro_export]
+#[doc(hidden)]
+/// The equivalent of the `check!` macro of the assert2 crate that only performs the check when debug
+/// assertions are enabled. We cannot just wrap `check!` inside an `if` statement because the
+/// guard that makes the test fail at the end would go out of scope at the end of the if block.
+/// Without debug assertions, the check still has to compile, just like the std lib `debug_assert!`.
+macro_rules! __debug_check {
+    ($($tokens:tt)*) => {
+        #[cfg(debug_assertions)]
+        $crate::assertions::check!($($tokens)*);
+        #[cfg(not(debug_assertions))]
+        if false {
+            $crate::assertions::check!($($tokens)*);
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Turn the result of a check into an optional guard, which makes the test fail once it goes out of
+/// scope. This is how the `check!` macro of assert2 works and the result must be bound to a variable
+/// in the scope of the check.
+macro_rules! __fail_guard {
+    ($check_result:expr) => {
+        match $check_result {
+            Ok(_) => None,
+            Err(_) => Some($crate::assertions::print::FailGuard(|| {
+                panic!("check failed")
+            })),
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Check an expression against a pattern with an optional guard, without panicking. The guard is
+/// only evaluated if the pattern matches, so it can use the bindings of the pattern.
+/// If the pattern does not match, the failure is printed together with the value of the expression.
+/// # Returns
+/// `Ok(())` if the check succeeded and `Err(())` if it failed, which is concluded by [__conclude]
+macro_rules! __check_matches {
+    ($macro_name:expr, $expression:expr, $($pattern:pat)|+, $(if $guard:expr)?, [$($binding:ident),*], [$($info_args:tt)*]) => {
+        match $expression {
+            $($pattern)|+ => $crate::__check_guard!($macro_name, $(if $guard)?, [$($binding),*], [$($info_args)*]),
+            #[allow(unreachable_patterns)]
+            ref value => {
+                let value = $crate::__maybe_debug!(value);
+                $crate::__print_failure!(
+                    $macro_name,
+                    [$($info_args)*],
+                    $crate::assertions::print::MatchExpr {
+                        print_let: true,
+                        value: &value,
+                        pattern: ::std::stringify!($($pattern)|+),
+                        expression: ::std::stringify!($expression),
+                    }
+                );
+                Err(())
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// print the failure of a negative pattern assertion `!matches!(expression, pattern)`, in which the
+/// value (if given) matched the pattern. The bindings of the pattern must be in scope.
+/// # Returns
+/// `Err(())`, which is concluded by [__conclude]
+macro_rules! __not_matches_failure {
+    ($macro_name:expr, $value:expr, $expression:expr, [$($pattern:tt)*], [$($binding:ident),*], [$($info_args:tt)*]) => {{
+        $crate::__print_failure!(
+            $macro_name,
+            [$($info_args)*],
+            $crate::assertions::pattern::NotMatchExpr {
+                value: $value,
+                pattern: ::std::stringify!($($pattern)*),
+                expression: ::std::stringify!($expression),
+            },
+            &[$(
+                (
+                    ::std::stringify!($binding),
+                    ::std::format!(
+                        "{:?}",
+                        $crate::__maybe_debug!(&$binding)
+                    )
+                    .as_str(),
+                )
+            ),*]
+        );
+        Err::<(), ()>(())
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Check the guard of a pattern if there is one. The bindings of the pattern must be in scope.
+/// If the guard fails, the failure is printed together with the values of the bindings.
+/// # Returns
+/// `Ok(())` if the check succeeded and `Err(())` if it failed, which is concluded by [__conclude]
+macro_rules! __check_guard {
+    ($macro_name:expr, , [$($binding:ident),*], [$($info_args:tt)*]) => {
+        Ok::<(), ()>(())
+    };
+    ($macro_name:expr, if $guard:expr, [$($binding:ident),*], [$($info_args:tt)*]) => {
+        if $guard {
+            Ok::<(), ()>(())
+        } else {
+            $crate::__print_failure!(
+                $macro_name,
+                [$($info_args)*],
+                $crate::assertions::print::BooleanExpr {
+                    expression: ::std::stringify!($guard),
+                },
+                &[$(
+                    (
+                        ::std::stringify!($binding),
+                        ::std::format!(
+                            "{:?}",
+                            $crate::__maybe_debug!(&$binding)
+                        )
+                        .as_str(),
+                    )
+                ),*]
+            );
+            Err(())
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Check the given conjuncts `(a) (b) ...` in order, without panicking. Just like with `a && b`,
+/// a conjunct is only checked if all the conjuncts before it succeeded.
+/// # Returns
+/// `Ok(())` if all checks succeeded and `Err(())` if one of them failed, which is concluded by [__conclude]
+macro_rules! __check_all {
+    ($macro_name:expr, [$($info_args:tt)*] ($($conjunct:tt)+)) => {
+        $crate::__check_conjunct!($macro_name, [$($info_args)*] $($conjunct)+)
+    };
+    ($macro_name:expr, [$($info_args:tt)*] ($($conjunct:tt)+) $(($($rest:tt)+))+) => {
+        match $crate::__check_conjunct!($macro_name, [$($info_args)*] $($conjunct)+) {
+            Ok(_) => $crate::__check_all!($macro_name, [$($info_args)*] $(($($rest)+))+),
+            Err(_) => Err::<(), ()>(()),
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper for [__check_all] that checks a single conjunct, which can be a `matches!` expression,
+/// one of the special forms below or any other boolean expression. The special forms are:
+/// * `@variant(receiver, method)`: for `receiver.is_ok()`, `is_err()`, `is_some()` or `is_none()`.
+///   If this fails, the value of the receiver is printed.
+/// * `@method(receiver, method, argument)`: for `receiver.contains(argument)`, `starts_with`, `ends_with`
+///   or `is_match`, where the argument is not a closure. If this fails, the values of the receiver and
+///   the argument are printed.
+/// * `@all(iterator, predicate)` and `@any(iterator, predicate)`: for `iterator.all(predicate)` and
+///   `iterator.any(predicate)`. The predicate is evaluated exactly as often as it would have been.
+///   If this fails, the first element that does not satisfy the predicate is printed for `all`
+///   and all elements are printed for `any`. Receivers that are not iterators are checked like any
+///   other boolean expression.
+/// * `@is_empty(receiver)` and `@is_empty(!receiver)`: for `receiver.is_empty()` and its negation.
+///   If this fails, the contents of the receiver are printed.
+/// * `@len_left(receiver, operator, right)` and `@len_right(left, operator, receiver)`: for comparisons
+///   like `receiver.len() == right`, where the length is on the left or right hand side. If this
+///   fails, the contents of the receiver are printed together with the compared values.
+/// * `@approx_eq(left, right, [tolerance = value, ...])`: for the `assert_approx_eq!` family of macros.
+///   If this fails, both numbers, their difference and the tolerance are printed.
+/// * `@abs_diff_left(minuend, subtrahend, operator, right)` and `@abs_diff_right(left, operator, minuend, subtrahend)`:
+///   for comparisons like `(minuend - subtrahend).abs() < right`, where the absolute difference is on the
+///   left or right hand side. If this fails, the minuend and subtrahend are printed together with the
+///   compared values.
+/// * `@not_matches(@bindings(...) matches!(expression, pattern))`: for `!matches!(expression, pattern)`,
+///   with an optional guard. If this fails, the value that matched is printed. If the pattern binds
+///   variables, which the value might have been moved into, their values are printed instead.
+/// * `@eq(left, right)`: for `left == right`. If this fails for two multi-line strings, a line based
+///   diff is printed. For two sequences (like slices or Vecs), the first index at which they differ
+///   and the elements around it are printed. For other values whose debug output is too long for a
+///   single line, like big structs and enums, the fields that differ are printed. For all other types,
+///   the output is the same as for `left == right`.
+macro_rules! __check_conjunct {
+    ($macro_name:expr, [$($info_args:tt)*] $(@bindings($($binding:ident),*))? $(::)? $(std::)? $(core::)? matches!($expression:expr, $($pattern:pat)|+ $( if $guard: expr )? $(,)?)) => {
+        $crate::__check_matches!($macro_name, $expression, $($pattern)|+, $(if $guard)?, [$($($binding),*)?], [$($info_args)*])
+    };
+    // without any bindings, the value can be borrowed while it is matched against the pattern
+    ($macro_name:expr, [$($info_args:tt)*] @not_matches(@bindings() $(::)? $(std::)? $(core::)? matches!($expression:expr, $($pattern:pat)|+ $( if $guard: expr )? $(,)?))) => {
+        match $expression {
+            ref value @ ($($pattern)|+) $(if $guard)? => {
+                let value = $crate::__maybe_debug!(value);
+                $crate::__not_matches_failure!($macro_name, Some(&value), $expression, [$($pattern)|+ $(if $guard)?], [], [$($info_args)*])
+            }
+            #[allow(unreachable_patterns)]
+            _ => Ok::<(), ()>(()),
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @not_matches($(@bindings($($binding:ident),*))? $(::)? $(std::)? $(core::)? matches!($expression:expr, $($pattern:pat)|+ $( if $guard: expr )? $(,)?))) => {
+        match $expression {
+            #[allow(unused_variables)]
+            $($pattern)|+ $(if $guard)? => {
+                $crate::__not_matches_failure!($macro_name, None::<&()>, $expression, [$($pattern)|+ $(if $guard)?], [$($($binding),*)?], [$($info_args)*])
+            }
+            #[allow(unreachable_patterns)]
+            _ => Ok::<(), ()>(()),
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @variant($receiver:expr, $method:ident)) => {
+        match &$receiver {
+            value if value.$method() => Ok::<(), ()>(()),
+            value => {
+                let value = $crate::__maybe_debug!(value);
+                $crate::__print_failure!(
+                    $macro_name,
+                    [$($info_args)*],
+                    $crate::assertions::print::MatchExpr {
+                        print_let: true,
+                        value: &value,
+                        pattern: $crate::__variant_pattern!($method),
+                        expression: ::std::stringify!($receiver),
+                    }
+                );
+                Err(())
+            }
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @is_empty(! $receiver:expr)) => {
+        match &$receiver {
+            collection if !collection.is_empty() => Ok::<(), ()>(()),
+            collection => $crate::__collection_failure!(
+                $macro_name,
+                [$($info_args)*],
+                collection,
+                $receiver,
+                $crate::assertions::print::BooleanExpr {
+                    expression: ::std::stringify!(!$receiver.is_empty()),
+                }
+            ),
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @is_empty($receiver:expr)) => {
+        match &$receiver {
+            collection if collection.is_empty() => Ok::<(), ()>(()),
+            collection => $crate::__collection_failure!(
+                $macro_name,
+                [$($info_args)*],
+                collection,
+                $receiver,
+                $crate::assertions::print::BooleanExpr {
+                    expression: ::std::stringify!($receiver.is_empty()),
+                }
+            ),
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @len_left($receiver:expr, $operator:tt, $right:expr)) => {
+        match &$receiver {
+            collection => match (&collection.len(), &$right) {
+                (left, right) if left $operator right => Ok::<(), ()>(()),
+                (left, right) => {
+                    let right = $crate::__maybe_debug!(right);
+                    $crate::__collection_failure!(
+                        $macro_name,
+                        [$($info_args)*],
+                        collection,
+                        $receiver,
+                        $crate::assertions::print::BinaryOp {
+                            left,
+                            right: &right,
+                            operator: ::std::stringify!($operator),
+                            left_expr: ::std::stringify!($receiver.len()),
+                            right_expr: ::std::stringify!($right),
+                        }
+                    )
+                }
+            },
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @len_right($left:expr, $operator:tt, $receiver:expr)) => {
+        match (&$left, &$receiver) {
+            (left, collection) => match (left, &collection.len()) {
+                (left, right) if left $operator right => Ok::<(), ()>(()),
+                (left, right) => {
+                    let left = $crate::__maybe_debug!(left);
+                    $crate::__collection_failure!(
+                        $macro_name,
+                        [$($info_args)*],
+                        collection,
+                        $receiver,
+                        $crate::assertions::print::BinaryOp {
+                            left: &left,
+                            right,
+                            operator: ::std::stringify!($operator),
+                            left_expr: ::std::stringify!($left),
+                            right_expr: ::std::stringify!($receiver.len()),
+                        }
+                    )
+                }
+            },
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @approx_eq($left:expr, $right:expr, [$($tolerance:ident = $value:expr,)*])) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let tolerance = $crate::assertions::approx::Tolerance::default()$(.$tolerance($value))*;
+                match $crate::assertions::approx::approx_eq(left, right, tolerance) {
+                    Ok(()) => Ok::<(), ()>(()),
+                    Err(failure) => {
+                        $crate::__print_failure!(
+                            $macro_name,
+                            [$($info_args)*],
+                            $crate::assertions::approx::ApproxExpr {
+                                failure,
+                                left_expr: ::std::stringify!($left),
+                                right_expr: ::std::stringify!($right),
+                                tolerance_expr: ::std::stringify!($($tolerance = $value),*),
+                            }
+                        );
+                        Err(())
+                    }
+                }
+            }
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @abs_diff_left($minuend:expr, $subtrahend:expr, $operator:tt, $right:expr)) => {
+        match ($minuend, $subtrahend) {
+            (minuend, subtrahend) => {
+                use $crate::assertions::maybe_copy::{IsCopy, IsNotCopy};
+                let operands = (
+                    (&&$crate::assertions::maybe_copy::Wrap(&minuend)).__assert2ify_copy(),
+                    (&&$crate::assertions::maybe_copy::Wrap(&subtrahend)).__assert2ify_copy(),
+                );
+                match (&(minuend - subtrahend).abs(), &$right) {
+                    (left, right) if left $operator right => Ok::<(), ()>(()),
+                    (left, right) => {
+                        let right = $crate::__maybe_debug!(right);
+                        let left = $crate::__maybe_debug!(left);
+                        $crate::__abs_diff_failure!(
+                            $macro_name,
+                            [$($info_args)*],
+                            operands,
+                            $minuend,
+                            $subtrahend,
+                            $crate::assertions::print::BinaryOp {
+                                left: &left,
+                                right: &right,
+                                operator: ::std::stringify!($operator),
+                                left_expr: ::std::stringify!(($minuend - $subtrahend).abs()),
+                                right_expr: ::std::stringify!($right),
+                            }
+                        )
+                    }
+                }
+            }
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @abs_diff_right($left:expr, $operator:tt, $minuend:expr, $subtrahend:expr)) => {
+        match &$left {
+            left => match ($minuend, $subtrahend) {
+                (minuend, subtrahend) => {
+                    use $crate::assertions::maybe_copy::{IsCopy, IsNotCopy};
+                    let operands = (
+                        (&&$crate::assertions::maybe_copy::Wrap(&minuend)).__assert2ify_copy(),
+                        (&&$crate::assertions::maybe_copy::Wrap(&subtrahend)).__assert2ify_copy(),
+                    );
+                    match (left, &(minuend - subtrahend).abs()) {
+                        (left, right) if left $operator right => Ok::<(), ()>(()),
+                        (left, right) => {
+                            let left = $crate::__maybe_debug!(left);
+                            let right = $crate::__maybe_debug!(right);
+                            $crate::__abs_diff_failure!(
+                                $macro_name,
+                                [$($info_args)*],
+                                operands,
+                                $minuend,
+                                $subtrahend,
+                                $crate::assertions::print::BinaryOp {
+                                    left: &left,
+                                    right: &right,
+                                    operator: ::std::stringify!($operator),
+                                    left_expr: ::std::stringify!($left),
+                                    right_expr: ::std::stringify!(($minuend - $subtrahend).abs()),
+                                }
+                            )
+                        }
+                    }
+                }
+            },
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @eq($left:expr, $right:expr)) => {
+        match (&$left, &$right) {
+            (left, right) if left == right => Ok::<(), ()>(()),
+            (left, right) => {
+                use $crate::assertions::diff::probes::*;
+                if let Some(diff) = (&&&&&&&&$crate::assertions::diff::Wrap(left, right)).__assert2ify_diff() {
+                    $crate::__print_failure!(
+                        $macro_name,
+                        [$($info_args)*],
+                        $crate::assertions::diff::DiffExpr {
+                            diff,
+                            left_expr: ::std::stringify!($left),
+                            right_expr: ::std::stringify!($right),
+                        }
+                    );
+                    Err(())
+                } else {
+                    let left = $crate::__maybe_debug!(left);
+                    let right = $crate::__maybe_debug!(right);
+                    $crate::__print_failure!(
+                        $macro_name,
+                        [$($info_args)*],
+                        $crate::assertions::print::BinaryOp {
+                            left: &left,
+                            right: &right,
+                            operator: "==",
+                            left_expr: ::std::stringify!($left),
+                            right_expr: ::std::stringify!($right),
+                        }
+                    );
+                    Err(())
+                }
+            }
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @compare($left:expr, $operator:tt, $right:expr)) => {
+        match (&$left, &$right) {
+            (left, right) if left $operator right => Ok::<(), ()>(()),
+            (left, right) => {
+                let left = $crate::__maybe_debug!(left);
+                let right = $crate::__maybe_debug!(right);
+                $crate::__print_failure!(
+                    $macro_name,
+                    [$($info_args)*],
+                    $crate::assertions::print::BinaryOp {
+                        left: &left,
+                        right: &right,
+                        operator: ::std::stringify!($operator),
+                        left_expr: ::std::stringify!($left),
+                        right_expr: ::std::stringify!($right),
+                    }
+                );
+                Err(())
+            }
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @method($receiver:expr, $method:ident, $argument:expr)) => {
+        match &$receiver {
+            receiver => {
+                use $crate::assertions::maybe_copy::{IsCopy, IsNotCopy};
+                let argument_copy;
+                // the argument is evaluated at the call site, like in the original method call, and
+                // moved into it, so we keep a copy for the output if we can
+                if receiver.$method({
+                    let argument = $argument;
+                    argument_copy = (&&$crate::assertions::maybe_copy::Wrap(&argument)).__assert2ify_copy();
+                    argument
+                }) {
+                    Ok::<(), ()>(())
+                } else {
+                    use $crate::assertions::method_call::{IsChar, IsNotText, IsText};
+                    let receiver_text = (&&&$crate::assertions::maybe_copy::Wrap(receiver)).__assert2ify_text();
+                    let argument_text = argument_copy
+                        .as_ref()
+                        .and_then(|argument| (&&&$crate::assertions::maybe_copy::Wrap(argument)).__assert2ify_text());
+                    let receiver = $crate::__maybe_debug!(receiver);
+                    let argument = argument_copy
+                        .as_ref()
+                        .map(|argument| $crate::__maybe_debug!(argument));
+                    $crate::__print_failure!(
+                        $macro_name,
+                        [$($info_args)*],
+                        $crate::assertions::method_call::MethodCallExpr {
+                            receiver: &receiver,
+                            argument: argument.as_ref(),
+                            receiver_text: receiver_text.as_deref(),
+                            argument_text: argument_text.as_deref(),
+                            method: ::std::stringify!($method),
+                            receiver_expr: ::std::stringify!($receiver),
+                            argument_expr: ::std::stringify!($argument),
+                        }
+                    );
+                    Err(())
+                }
+            }
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @all($iterator:expr, $predicate:expr)) => {
+        match $crate::__check_iterator!(all, $iterator, $predicate, false) {
+            Ok(()) => Ok::<(), ()>(()),
+            Err(Some((count, copies))) => {
+                // only the copy of the last element, which did not satisfy the predicate, is kept
+                let element = copies.and_then(|mut copies| copies.pop());
+                let element = element
+                    .as_ref()
+                    .map(|element| $crate::__maybe_debug!(element));
+                $crate::__print_failure!(
+                    $macro_name,
+                    [$($info_args)*],
+                    $crate::assertions::iterator::AllExpr {
+                        index: count - 1,
+                        element: element.as_ref(),
+                        iterator_expr: ::std::stringify!($iterator),
+                        predicate_expr: ::std::stringify!($predicate),
+                    }
+                );
+                Err(())
+            }
+            Err(None) => $crate::__print_boolean_failure!($macro_name, [$($info_args)*], $iterator.all($predicate)),
+        }
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @any($iterator:expr, $predicate:expr)) => {
+        match $crate::__check_iterator!(any, $iterator, $predicate, true) {
+            Ok(()) => Ok::<(), ()>(()),
+            Err(Some((count, copies))) => {
+                let elements = copies.as_ref().map(|copies| {
+                    copies
+                        .iter()
+                        .map(|element| $crate::__maybe_debug!(element))
+                        .collect::<::std::vec::Vec<_>>()
+                });
+                $crate::__print_failure!(
+                    $macro_name,
+                    [$($info_args)*],
+                    $crate::assertions::iterator::AnyExpr {
+                        count,
+                        elements: elements.as_deref(),
+                        iterator_expr: ::std::stringify!($iterator),
+                        predicate_expr: ::std::stringify!($predicate),
+                    }
+                );
+                Err(())
+            }
+            Err(None) => $crate::__print_boolean_failure!($macro_name, [$($info_args)*], $iterator.any($predicate)),
+        }
+    };
+    // the `matches!` macros that the arms above can't parse are checked like any other condition
+    ($macro_name:expr, [$($info_args:tt)*] @bindings($($binding:ident),*) $($conjunct:tt)+) => {
+        $crate::__check_conjunct!($macro_name, [$($info_args)*] $($conjunct)+)
+    };
+    ($macro_name:expr, [$($info_args:tt)*] @not_matches(@bindings($($binding:ident),*) $($negated:tt)+)) => {
+        $crate::__check_conjunct!($macro_name, [$($info_args)*] !$($negated)+)
+    };
+    ($macro_name:expr, [$($info_args:tt)*] $($condition:tt)+) => {
+        if ($($condition)+) {
+            Ok::<(), ()>(())
+        } else {
+            $crate::__print_boolean_failure!($macro_name, [$($info_args)*], $($condition)+)
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper that prints the failure of a check on a boolean expression, which is given as tokens
+/// for the output and not evaluated
+/// # Returns
+/// `Err(())`, which is concluded by [__conclude]
+macro_rules! __print_boolean_failure {
+    ($macro_name:expr, [$($info_args:tt)*], $($condition:tt)+) => {{
+        $crate::__print_failure!(
+            $macro_name,
+            [$($info_args)*],
+            $crate::assertions::print::BooleanExpr {
+                expression: ::std::stringify!($($condition)+),
+            }
+        );
+        Err::<(), ()>(())
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper that evaluates `receiver.all(predicate)` or `receiver.any(predicate)`, where the
+/// predicate is given to the method as is, so that the compiler can infer the argument types of a
+/// closure. If the receiver is an iterator, the elements it gives are recorded on the way. The
+/// copies of all elements are kept if `keep_all` is true, otherwise only the copy of the last one.
+/// # Returns
+/// `Ok(())` if the method returned true. Otherwise `Err(Some((count, copies)))` with the number of
+/// elements and their copies (None if they are not Copy) for iterators and `Err(None)` for
+/// any other receiver.
+macro_rules! __check_iterator {
+    ($method:ident, $iterator:expr, $predicate:expr, $keep_all:expr) => {
+        // a match keeps the temporaries of the receiver alive, e.g. for `vec![..].iter()`
+        match $iterator {
+            #[allow(unused_mut)]
+            mut receiver => {
+                use $crate::assertions::iterator::{IsIterator, IsNotIterator};
+                use $crate::assertions::maybe_copy::{IsCopy, IsNotCopy};
+                let tag = (&&$crate::assertions::iterator::Wrap(&receiver)).__assert2ify_iterator_tag();
+                // the elements are moved into the predicate, so we keep copies for the output if we can
+                let record = tag.record(
+                    |element| (&&$crate::assertions::maybe_copy::Wrap(element)).__assert2ify_copy(),
+                    $keep_all,
+                );
+                if tag.prepare(receiver, &record).$method($predicate) {
+                    Ok(())
+                } else if tag.is_iterator() {
+                    Err(Some(record.into_parts()))
+                } else {
+                    Err(None)
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper that prints a failed check with the given expression, which implements
+/// [crate::assertions::print::CheckExpression], and optional fragments, i.e. pairs of the code
+/// and debug output of the values that are printed after the expression
+macro_rules! __print_failure {
+    ($macro_name:expr, [$($info_args:tt)*], $expression:expr) => {
+        $crate::__print_failure!($macro_name, [$($info_args)*], $expression, &[])
+    };
+    ($macro_name:expr, [$($info_args:tt)*], $expression:expr, $fragments:expr) => {{
+        $crate::assertions::print::FailedCheck {
+            macro_name: $macro_name,
+            file: ::std::file!(),
+            line: ::std::line!(),
+            column: ::std::column!(),
+            custom_msg: $crate::__custom_message!($($info_args)*),
+            expression: $expression,
+            fragments: $fragments,
+        }
+        .print();
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper that wraps a reference to a value, so that its debug output is the debug output of the
+/// value if it implements Debug and a placeholder otherwise
+macro_rules! __maybe_debug {
+    ($value:expr) => {
+        match $value {
+            value => {
+                use $crate::assertions::maybe_debug::{IsDebug, IsNotDebug};
+                (&&$crate::assertions::maybe_debug::Wrap(value))
+                    .__assert2ify_maybe_debug()
+                    .wrap(value)
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper that prints the failure of a check on `collection.len()` or `collection.is_empty()`
+/// with the given expression, followed by the (truncated) contents of the collection.
+macro_rules! __collection_failure {
+    ($macro_name:expr, [$($info_args:tt)*], $collection:ident, $receiver:expr, $expression:expr) => {{
+        let $collection = $crate::__maybe_debug!($collection);
+        let contents = $crate::assertions::collection::truncated_debug(&$collection);
+        $crate::__print_failure!(
+            $macro_name,
+            [$($info_args)*],
+            $expression,
+            &[(::std::stringify!($receiver), contents.as_str())]
+        );
+        Err(())
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper that prints the failure of a check on `(minuend - subtrahend).abs()` with the given
+/// expression, followed by the values of the minuend and subtrahend. The operands are the copies
+/// of both values, which are None if a value is not Copy and was moved by the subtraction.
+macro_rules! __abs_diff_failure {
+    ($macro_name:expr, [$($info_args:tt)*], $operands:ident, $minuend:expr, $subtrahend:expr, $expression:expr) => {{
+        let (minuend, subtrahend) = $operands;
+        let mut fragments = ::std::vec::Vec::new();
+        if let Some(minuend) = &minuend {
+            let minuend = $crate::__maybe_debug!(minuend);
+            fragments.push((::std::stringify!($minuend), ::std::format!("{:?}", minuend)));
+        }
+        if let Some(subtrahend) = &subtrahend {
+            let subtrahend = $crate::__maybe_debug!(subtrahend);
+            fragments.push((::std::stringify!($subtrahend), ::std::format!("{:?}", subtrahend)));
+        }
+        // literals like `1.0 = 1.0` are not worth printing
+        let fragments: ::std::vec::Vec<(&str, &str)> = fragments
+            .iter()
+            .filter(|(name, value)| name != value)
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect();
+        $crate::__print_failure!(
+            $macro_name,
+            [$($info_args)*],
+            $expression,
+            &fragments
+        );
+        Err(())
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper that gives the pattern that corresponds to the `is_ok()`, `is_err()`, `is_some()`
+/// or `is_none()` methods, for the output of a failed check
+macro_rules! __variant_pattern {
+    (is_ok) => {
+        "Ok(_)"
+    };
+    (is_err) => {
+        "Err(_)"
+    };
+    (is_some) => {
+        "Some(_)"
+    };
+    (is_none) => {
+        "None"
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// the failure of the replacement for a `match`, `if let` or `let ... else` in which all patterns
+/// but one diverge with `panic!` or `unreachable!`. It prints the value that did not match the
+/// pattern like `let_assert!` does and gives `false`, because it is the guard of an arm that
+/// must be skipped, so that the original diverging arms panic with their own message.
+macro_rules! __let_assert_failure {
+    ($value:ident, $expression:expr, [$($pattern:tt)*], [$($info_args:tt)*]) => {{
+        let $value = $crate::__maybe_debug!($value);
+        $crate::__print_failure!(
+            "let_assert",
+            [$($info_args)*],
+            $crate::assertions::print::MatchExpr {
+                print_let: true,
+                value: &$value,
+                pattern: ::std::stringify!($($pattern)*),
+                expression: ::std::stringify!($expression),
+            }
+        );
+        false
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper that turns the info arguments of an assertion into the optional custom message for assert2
+macro_rules! __custom_message {
+    () => {
+        None
+    };
+    ($($info_args:tt)+) => {
+        Some(::std::format_args!($($info_args)+))
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+///TODO DOCUMENT
+macro_rules! __xify {
+    // the proc macro replaces the idiom `if condition { panic!(...) }` by an assertion on the negated
+    // condition, which panics with the original `panic!` if it fails
+    (new_assertion = $new_assertion:ident, @if_panic($($panic:tt)+) [$($info_args:tt)*] @and $(($($conjunct:tt)+))+) => {
+        $crate::__conclude!($new_assertion, $crate::__check_all!(::std::stringify!($new_assertion), [$($info_args)*] $(($($conjunct)+))+), $($panic)+)
+    };
+    (new_assertion = $new_assertion:ident, @if_panic($($panic:tt)+) [$($info_args:tt)*] $($condition:tt)+) => {
+        $crate::__conclude!($new_assertion, $crate::__check_all!(::std::stringify!($new_assertion), [$($info_args)*] ($($condition)+)), $($panic)+)
+    };
+    // the proc macro splits assertions on conjunctions `a && b` into the conjuncts `@and (a) (b)`
+    (new_assertion = $new_assertion:ident, @and $(($($conjunct:tt)+))+ $(, $($info_args:tt)*)?) => {
+        $crate::__conclude!($new_assertion, $crate::__check_all!(::std::stringify!($new_assertion), [$($($info_args)*)?] $(($($conjunct)+))+))
+    };
+    // the proc macro gives us the variables that are bound by the pattern (if it could parse it),
+    // so we can print their values if the guard fails. The value is matched in place, so unlike
+    // with let_assert! it is not moved and the pattern may have alternatives on the top level.
+    // Checks must not panic right away and their guard has to live in the scope of the assertion
+    // we replace, which is taken care of by __conclude.
+    (new_assertion = $new_assertion:ident, $(@bindings($($binding:ident),*))? $(::)? $(std::)? $(core::)? matches!($expression:expr, $($pattern:pat)|+ $( if $guard: expr )? $(,)?) $(, $($info_args:tt)*)?) => {
+        $crate::__conclude!($new_assertion, $crate::__check_matches!(::std::stringify!($new_assertion), $expression, $($pattern)|+, $(if $guard)?, [$($($binding),*)?], [$($($info_args)*)?]))
+    };
+    // the `matches!` macros that the arms above can't parse are asserted like any other condition
+    (new_assertion = $new_assertion:ident, @bindings($($binding:ident),*) $($args:tt)+) => {
+        $crate::__xify!(new_assertion = $new_assertion, $($args)+)
+    };
+    // any other special form `@form(...)` the proc macro gives us, see __check_conjunct
+    (new_assertion = $new_assertion:ident, @$form:ident ($($form_args:tt)*) $(, $($info_args:tt)*)?) => {
+        $crate::__conclude!($new_assertion, $crate::__check_conjunct!(::std::stringify!($new_assertion), [$($($info_args)*)?] @$form($($form_args)*)))
+    };
+    (new_assertion = $new_assertion:ident, $($args:tt)+) => {
+        $crate::assertions::$new_assertion!($($args)+);
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Conclude a check that produced the given result (`Ok(())` or `Err(())`), depending on
+/// the style of the assertion. Assertions panic right away if the result is an error, while
+/// checks make the test fail at the end of the scope. If a diverging macro like `panic!(...)`
+/// is given, assertions invoke it instead of panicking with the default message.
+macro_rules! __conclude {
+    (check, $check_result:expr $(, $($panic:tt)+)?) => {
+        let _guard = $crate::__fail_guard!($check_result);
+    };
+    (debug_check, $check_result:expr $(, $($panic:tt)+)?) => {
+        let _guard = if ::std::cfg!(debug_assertions) {
+            $crate::__fail_guard!($check_result)
+        } else {
+            None
+        };
+    };
+    ($new_assertion:ident, $check_result:expr) => {
+        if let Err(()) = $check_result {
+            panic!("assertion failed");
+        }
+    };
+    ($new_assertion:ident, $check_result:expr, $($panic:tt)+) => {
+        if let Err(()) = $check_result {
+            $($panic)+
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+///TODO DOCUMENT
+macro_rules! __assertify {
+    ($($args:tt)+) => {
+        $crate::__xify!(new_assertion=assert, $($args)+)
+    };
+
+    () => {::std::compile_error!("Too few arguments in assertion")}
+}
+
+#[macro_export]
+#[doc(hidden)]
+///TODO DOCUMENT
+macro_rules! __checkify {
+    ($($args:tt)+) => {
+        $crate::__xify!(new_assertion=check, $($args)+)
+    };
+    () => {::std::compile_error!("Too few arguments in assertion")}
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// the replacement for the debug assertions in the assertify style. The assertion
+/// is only checked if debug assertions are enabled, just like the std lib `debug_assert!`
+macro_rules! __debug_assertify {
+    ($($args:tt)+) => {
+        if ::std::cfg!(debug_assertions) {
+            $crate::__xify!(new_assertion=debug_assert, $($args)+)
+        }
+    };
+    () => {::std::compile_error!("Too few arguments in assertion")}
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// the replacement for the debug assertions in the checkify style. The check
+/// is only performed if debug assertions are enabled, just like the std lib `debug_assert!`
+macro_rules! __debug_checkify {
+    ($($args:tt)+) => {
+        $crate::__xify!(new_assertion=debug_check, $($args)+)
+    };
+    () => {::std::compile_error!("Too few arguments in assertion")}
+}
@@ -0,0 +1,47 @@
+//! Helpers for the negative pattern assertion `assert!(!matches!(expression, pattern))`, whose
+//! output shows the value that matched the pattern although it must not.
+
+use std::fmt::{Debug, Write};
+
+use yansi::Paint;
+
+use crate::assertions::print::CheckExpression;
+
+/// The expression of a failed negative pattern assertion
+pub struct NotMatchExpr<'a, Value> {
+    /// the value that matched the pattern. This is None if the pattern binds variables, because
+    /// the value might have been moved into them. Their values are printed instead.
+    pub value: Option<&'a Value>,
+    /// the pattern (including the guard) as it was written in the code
+    pub pattern: &'a str,
+    /// the expression as it was written in the code
+    pub expression: &'a str,
+}
+
+impl<Value: Debug> CheckExpression for NotMatchExpr<'_, Value> {
+    fn write_expression(&self, buffer: &mut String) {
+        write!(
+            buffer,
+            "{}{}{}, {}{}",
+            Paint::blue("!").bold(),
+            Paint::blue("matches!(").bold(),
+            Paint::yellow(self.expression),
+            Paint::cyan(self.pattern),
+            Paint::blue(")").bold()
+        )
+        .unwrap();
+    }
+
+    fn write_expansion(&self, buffer: &mut String) {
+        if let Some(value) = self.value {
+            writeln!(buffer, "with expansion:").unwrap();
+            writeln!(buffer, "  {}", Paint::yellow(&format!("{:?}", value))).unwrap();
+        }
+        write!(
+            buffer,
+            "{}",
+            "Note: the value matches the pattern, but it must not".red()
+        )
+        .unwrap();
+    }
+}
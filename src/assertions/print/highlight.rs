@@ -0,0 +1,220 @@
+// Adapted from the `__assert2_impl::print::diff` module of assert2 0.3.16 (https://github.com/de-vri-es/assert2-rs).
+// Copyright (c) 2020-2024, Maarten de Vries <maarten@de-vri.es>
+// Licensed under the BSD 2-Clause License, see the LICENSE file for its conditions and disclaimer.
+
+//! The highlighted differences between the debug output of the left and right hand side of a
+//! failed comparison, which look just like those of assert2.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use yansi::{Color, Paint, Style};
+
+/// The line based difference between two multi-line strings, in which the lines that were
+/// replaced by exactly one other line are highlighted word by word
+pub struct LinesDiff<'a> {
+    lines: Vec<Line<'a>>,
+}
+
+/// A line of a [LinesDiff]
+enum Line<'a> {
+    /// a line that only the left string has
+    LeftOnly(&'a str),
+    /// a line that only the right string has
+    RightOnly(&'a str),
+    /// a line of the left string that was replaced by a line of the right string
+    Different(&'a str, &'a str),
+    /// a line that both strings have
+    Equal(&'a str),
+}
+
+impl<'a> LinesDiff<'a> {
+    pub fn new(left: &'a str, right: &'a str) -> Self {
+        let mut lines = Vec::new();
+        // the number of left lines directly before the current line
+        let mut left_run = 0;
+        for result in diff::lines(left, right) {
+            match result {
+                diff::Result::Left(left) => {
+                    lines.push(Line::LeftOnly(left));
+                    left_run += 1;
+                    continue;
+                }
+                diff::Result::Right(right) => match lines.last_mut() {
+                    // exactly one left line followed by a right line is a replaced line
+                    Some(last @ Line::LeftOnly(_)) if left_run == 1 => {
+                        if let Line::LeftOnly(left) = *last {
+                            *last = Line::Different(left, right);
+                        }
+                    }
+                    // but a line that was replaced by several lines is not
+                    Some(last @ Line::Different(..)) => {
+                        if let Line::Different(left, previous_right) = *last {
+                            *last = Line::LeftOnly(left);
+                            lines.push(Line::RightOnly(previous_right));
+                            lines.push(Line::RightOnly(right));
+                        }
+                    }
+                    _ => lines.push(Line::RightOnly(right)),
+                },
+                diff::Result::Both(line, _) => lines.push(Line::Equal(line)),
+            }
+            left_run = 0;
+        }
+        Self { lines }
+    }
+
+    /// write the lines of both strings, interleaved with each other, without a trailing newline
+    pub fn write_interleaved(&self, buffer: &mut String) {
+        for line in &self.lines {
+            match *line {
+                Line::LeftOnly(left) => {
+                    writeln!(buffer, "{}", Paint::cyan(&format!("< {}", left))).unwrap();
+                }
+                Line::RightOnly(right) => {
+                    writeln!(buffer, "{}", Paint::yellow(&format!("> {}", right))).unwrap();
+                }
+                Line::Different(left, right) => {
+                    let diff = WordsDiff::new(left, right);
+                    write!(buffer, "{} ", "<".paint(diff.left.normal)).unwrap();
+                    diff.write_left(buffer);
+                    write!(buffer, "\n{} ", ">".paint(diff.right.normal)).unwrap();
+                    diff.write_right(buffer);
+                    buffer.push('\n');
+                }
+                Line::Equal(text) => {
+                    writeln!(buffer, "  {}", text.primary().on_primary().dim()).unwrap();
+                }
+            }
+        }
+        buffer.pop();
+    }
+}
+
+/// The word based difference between two lines, in which the differing words are highlighted
+pub struct WordsDiff<'a> {
+    left_line: &'a str,
+    right_line: &'a str,
+    left: Highlights,
+    right: Highlights,
+}
+
+impl<'a> WordsDiff<'a> {
+    pub fn new(left_line: &'a str, right_line: &'a str) -> Self {
+        let left_words = words_of(left_line);
+        let right_words = words_of(right_line);
+        let mut left = Highlights::new(Color::Cyan);
+        let mut right = Highlights::new(Color::Yellow);
+        for result in diff::slice(&left_words, &right_words) {
+            match result {
+                diff::Result::Left(word) => left.push(word.len(), true),
+                diff::Result::Right(word) => right.push(word.len(), true),
+                diff::Result::Both(left_word, right_word) => {
+                    left.push(left_word.len(), false);
+                    right.push(right_word.len(), false);
+                }
+            }
+        }
+        Self {
+            left_line,
+            right_line,
+            left,
+            right,
+        }
+    }
+
+    /// write the left line with its highlighted words, without a newline
+    pub fn write_left(&self, buffer: &mut String) {
+        self.left.write(buffer, self.left_line);
+    }
+
+    /// write the right line with its highlighted words, without a newline
+    pub fn write_right(&self, buffer: &mut String) {
+        self.right.write(buffer, self.right_line);
+    }
+}
+
+/// split the line into words, which are runs of letters (split before an uppercase letter that
+/// follows a lowercase one), runs of digits, runs of whitespace and single other characters
+fn words_of(mut line: &str) -> Vec<&str> {
+    let is_break = |a: char, b: char| {
+        if a.is_alphabetic() {
+            !b.is_alphabetic() || (a.is_lowercase() && !b.is_lowercase())
+        } else if a.is_ascii_digit() {
+            !b.is_ascii_digit()
+        } else if a.is_whitespace() {
+            !b.is_whitespace()
+        } else {
+            true
+        }
+    };
+
+    let mut words = Vec::new();
+    while !line.is_empty() {
+        let end = line
+            .chars()
+            .zip(line.char_indices().skip(1))
+            .find(|&(a, (_, b))| is_break(a, b))
+            .map(|(_, (index, _))| index)
+            .unwrap_or_else(|| line.len());
+        let (word, rest) = line.split_at(end);
+        words.push(word);
+        line = rest;
+    }
+    words
+}
+
+/// The ranges of a line that are highlighted or not
+struct Highlights {
+    ranges: Vec<(bool, Range<usize>)>,
+    /// the number of highlighted bytes
+    highlighted: usize,
+    /// the style of the text that is not highlighted
+    normal: Style,
+    /// the style of the highlighted text
+    highlight: Style,
+}
+
+impl Highlights {
+    fn new(color: Color) -> Self {
+        Self {
+            ranges: Vec::new(),
+            highlighted: 0,
+            normal: Style::new().fg(color),
+            highlight: Style::new().fg(Color::Black).bg(color).bold(),
+        }
+    }
+
+    /// append the given number of bytes, which are highlighted or not
+    fn push(&mut self, length: usize, highlight: bool) {
+        if highlight {
+            self.highlighted += length;
+        }
+        match self.ranges.last_mut() {
+            Some((last_highlight, range)) if *last_highlight == highlight => range.end += length,
+            Some((_, range)) => {
+                let start = range.end;
+                self.ranges.push((highlight, start..start + length));
+            }
+            None => self.ranges.push((highlight, 0..length)),
+        }
+    }
+
+    /// write the line with the highlights. If less than a third of the line stays the same,
+    /// nothing is highlighted, because the highlights would not help to spot the differences.
+    fn write(&self, buffer: &mut String, line: &str) {
+        let not_highlighted = line.len() - self.highlighted;
+        if 2 * not_highlighted < self.highlighted {
+            write!(buffer, "{}", line.paint(self.normal)).unwrap();
+        } else {
+            for (highlight, range) in self.ranges.iter().cloned() {
+                let style = if highlight {
+                    self.highlight
+                } else {
+                    self.normal
+                };
+                write!(buffer, "{}", line[range].paint(style)).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+// Adapted from the `__assert2_impl::print` module of assert2 0.3.16 (https://github.com/de-vri-es/assert2-rs).
+// Copyright (c) 2020-2024, Maarten de Vries <maarten@de-vri.es>
+// Licensed under the BSD 2-Clause License, see the LICENSE file for its conditions and disclaimer.
+
+//! The output of failed checks, which looks just like that of assert2. The expressions of the
+//! checks implement [CheckExpression], which writes the expression as it was written in the code
+//! and its expansion, i.e. the values that made the check fail.
+
+use std::fmt::{Arguments, Debug, Write};
+
+use yansi::Paint;
+
+use crate::assertions::print::highlight::{LinesDiff, WordsDiff};
+use crate::assertions::print::options::{is_compact_good, Expansion, Options};
+
+pub mod highlight;
+pub mod options;
+
+/// The expression of a failed check
+pub trait CheckExpression {
+    /// write the expression as it was written in the code, without a newline
+    fn write_expression(&self, buffer: &mut String);
+    /// write the expansion of the expression, usually starting with a line like `with expansion:`,
+    /// without a trailing newline
+    fn write_expansion(&self, buffer: &mut String);
+}
+
+/// A failed check, which is printed to stderr
+pub struct FailedCheck<'a, Expression> {
+    /// the name of the macro that is printed, e.g. `check`
+    pub macro_name: &'a str,
+    pub file: &'a str,
+    pub line: u32,
+    pub column: u32,
+    /// the message of the info arguments of the assertion, if there are any
+    pub custom_msg: Option<Arguments<'a>>,
+    pub expression: Expression,
+    /// pairs of the code and the debug output of values that are printed after the expression
+    pub fragments: &'a [(&'a str, &'a str)],
+}
+
+impl<Expression: CheckExpression> FailedCheck<'_, Expression> {
+    pub fn print(&self) {
+        let mut buffer = String::new();
+        writeln!(
+            buffer,
+            "{} at {}:{}:{}:",
+            "Assertion failed".red().bold(),
+            self.file.bold(),
+            self.line,
+            self.column
+        )
+        .unwrap();
+        write!(
+            buffer,
+            "  {}{} ",
+            Paint::magenta(self.macro_name),
+            Paint::magenta("!(")
+        )
+        .unwrap();
+        self.expression.write_expression(&mut buffer);
+        writeln!(buffer, " {}", Paint::magenta(")")).unwrap();
+        if !self.fragments.is_empty() {
+            writeln!(buffer, "with:").unwrap();
+            for (name, value) in self.fragments {
+                writeln!(
+                    buffer,
+                    "  {} {} {}",
+                    Paint::magenta(name),
+                    Paint::blue("=").bold(),
+                    value
+                )
+                .unwrap();
+            }
+        }
+        self.expression.write_expansion(&mut buffer);
+        writeln!(buffer).unwrap();
+        if let Some(message) = self.custom_msg {
+            writeln!(buffer, "with message:").unwrap();
+            writeln!(buffer, "  {}", message.bold()).unwrap();
+        }
+        writeln!(buffer).unwrap();
+        if !Options::from_env().color {
+            buffer = without_colors(&buffer);
+        }
+        eprint!("{}", buffer);
+    }
+}
+
+/// the text without the ANSI escape sequences that color it. The output is written with colors
+/// (unless yansi is disabled globally) and they are removed afterwards if they are not wanted,
+/// because the color settings of yansi are global and belong to the program that is tested.
+fn without_colors(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("\x1b[") {
+        result.push_str(&rest[..start]);
+        match rest[start..].find('m') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The expression of a failed comparison `left operator right`
+pub struct BinaryOp<'a, Left, Right> {
+    pub left: &'a Left,
+    pub right: &'a Right,
+    pub operator: &'a str,
+    /// the left hand side as it was written in the code
+    pub left_expr: &'a str,
+    /// the right hand side as it was written in the code
+    pub right_expr: &'a str,
+}
+
+impl<Left: Debug, Right: Debug> CheckExpression for BinaryOp<'_, Left, Right> {
+    fn write_expression(&self, buffer: &mut String) {
+        write!(
+            buffer,
+            "{} {} {}",
+            Paint::cyan(self.left_expr),
+            Paint::blue(self.operator).bold(),
+            Paint::yellow(self.right_expr)
+        )
+        .unwrap();
+    }
+
+    fn write_expansion(&self, buffer: &mut String) {
+        let expansion = Options::from_env().expansion;
+        if expansion != Expansion::Pretty {
+            let left = format!("{:?}", self.left);
+            let right = format!("{:?}", self.right);
+            if expansion == Expansion::Compact || is_compact_good(&[&left, &right]) {
+                writeln!(buffer, "with expansion:").unwrap();
+                let diff = WordsDiff::new(&left, &right);
+                buffer.push_str("  ");
+                diff.write_left(buffer);
+                write!(buffer, " {} ", Paint::blue(self.operator)).unwrap();
+                diff.write_right(buffer);
+                if left == right {
+                    let note = if self.operator == "==" {
+                        "Note: Left and right compared as unequal, but the Debug output of left and right is identical!".red()
+                    } else {
+                        "Note: Debug output of left and right is identical.".bold()
+                    };
+                    write!(buffer, "\n{}", note).unwrap();
+                }
+                return;
+            }
+        }
+        let left = format!("{:#?}", self.left);
+        let right = format!("{:#?}", self.right);
+        writeln!(buffer, "with diff:").unwrap();
+        LinesDiff::new(&left, &right).write_interleaved(buffer);
+    }
+}
+
+/// The expression of a failed check on a boolean expression
+pub struct BooleanExpr<'a> {
+    /// the expression as it was written in the code
+    pub expression: &'a str,
+}
+
+impl CheckExpression for BooleanExpr<'_> {
+    fn write_expression(&self, buffer: &mut String) {
+        write!(buffer, "{}", Paint::cyan(self.expression)).unwrap();
+    }
+
+    fn write_expansion(&self, buffer: &mut String) {
+        writeln!(buffer, "with expansion:").unwrap();
+        write!(buffer, "  {:?}", false.cyan()).unwrap();
+    }
+}
+
+/// The expression of a failed pattern match `let pattern = expression`
+pub struct MatchExpr<'a, Value> {
+    /// whether the expression is printed with a leading `let`
+    pub print_let: bool,
+    /// the value that did not match the pattern
+    pub value: &'a Value,
+    /// the pattern as it was written in the code
+    pub pattern: &'a str,
+    /// the expression as it was written in the code
+    pub expression: &'a str,
+}
+
+impl<Value: Debug> CheckExpression for MatchExpr<'_, Value> {
+    fn write_expression(&self, buffer: &mut String) {
+        if self.print_let {
+            write!(buffer, "{} ", Paint::blue("let").bold()).unwrap();
+        }
+        write!(
+            buffer,
+            "{} {} {}",
+            Paint::cyan(self.pattern),
+            Paint::blue("=").bold(),
+            Paint::yellow(self.expression)
+        )
+        .unwrap();
+    }
+
+    fn write_expansion(&self, buffer: &mut String) {
+        writeln!(buffer, "with expansion:").unwrap();
+        let [value] = Options::from_env().expansion.expand_all([&self.value]);
+        let value = value.yellow().to_string();
+        for line in value.lines() {
+            writeln!(buffer, "  {}", line).unwrap();
+        }
+        buffer.pop();
+    }
+}
+
+/// A guard that makes a failed check fail the test once it goes out of scope, unless the thread
+/// is already panicking. The panic is in a closure, so that it points to the location of the check.
+pub struct FailGuard<Fail: FnMut()>(pub Fail);
+
+impl<Fail: FnMut()> Drop for FailGuard<Fail> {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            (self.0)()
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// Adapted from the `__assert2_impl::print::options` module of assert2 0.3.16 (https://github.com/de-vri-es/assert2-rs).
+// Copyright (c) 2020-2024, Maarten de Vries <maarten@de-vri.es>
+// Licensed under the BSD 2-Clause License, see the LICENSE file for its conditions and disclaimer.
+
+//! The settings of the output, which respect the same environment variables as assert2.
+
+use std::env;
+use std::ffi::OsStr;
+
+/// How the values in the expansion of a failed check are formatted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expansion {
+    /// the compact debug output if it is short enough for all values and the pretty debug
+    /// output otherwise
+    Auto,
+    /// always the pretty debug output `{:#?}`
+    Pretty,
+    /// always the compact debug output `{:?}`
+    Compact,
+}
+
+impl Expansion {
+    /// the debug output of the given values, which are all formatted in the same way
+    pub fn expand_all<const N: usize>(self, values: [&dyn std::fmt::Debug; N]) -> [String; N] {
+        if self != Self::Pretty {
+            let compact = values.map(|value| format!("{:?}", value));
+            if self == Self::Compact || is_compact_good(&compact) {
+                return compact;
+            }
+        }
+        values.map(|value| format!("{:#?}", value))
+    }
+}
+
+/// whether the compact debug output of all values is short enough to print it instead of the
+/// pretty debug output
+pub fn is_compact_good(expanded: &[impl AsRef<str>]) -> bool {
+    expanded
+        .iter()
+        .all(|value| value.as_ref().len() <= 40 && !value.as_ref().contains('\n'))
+}
+
+/// The settings of the output
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub expansion: Expansion,
+    /// whether the output is colored
+    pub color: bool,
+}
+
+impl Options {
+    /// The settings from the environment, just like assert2 reads them. The `ASSERT2` variable
+    /// is a comma separated list of the options `pretty`, `compact`, `color` and `no-color`.
+    /// Without `color` or `no-color`, the output is colored if it goes to a terminal, unless
+    /// the `NO_COLOR`, `CLICOLOR` or `CLICOLOR_FORCE` variables say otherwise.
+    pub fn from_env() -> Self {
+        let mut options = Self {
+            expansion: Expansion::Auto,
+            color: should_color(),
+        };
+        let words = env::var_os("ASSERT2");
+        let words = words
+            .as_ref()
+            .and_then(|words| words.to_str())
+            .unwrap_or("");
+        for word in words.split(',').map(str::trim) {
+            if word.eq_ignore_ascii_case("pretty") {
+                options.expansion = Expansion::Pretty;
+            } else if word.eq_ignore_ascii_case("compact") {
+                options.expansion = Expansion::Compact;
+            } else if word.eq_ignore_ascii_case("color") {
+                options.color = true;
+            } else if word.eq_ignore_ascii_case("no-color") {
+                options.color = false;
+            }
+        }
+        options
+    }
+}
+
+/// whether the output should be colored according to the `NO_COLOR`, `CLICOLOR` and
+/// `CLICOLOR_FORCE` variables or whether it goes to a terminal otherwise
+fn should_color() -> bool {
+    let is_true = |value: &OsStr| {
+        value == "1" || value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("yes")
+    };
+    let is_false = |value: &OsStr| {
+        value == "0" || value.eq_ignore_ascii_case("false") || value.eq_ignore_ascii_case("no")
+    };
+    let variable_is = |name: &str, predicate: &dyn Fn(&OsStr) -> bool| matches!(env::var_os(name), Some(value) if predicate(&value));
+
+    if variable_is("NO_COLOR", &is_true) || variable_is("CLICOLOR", &is_false) {
+        false
+    } else if variable_is("CLICOLOR_FORCE", &is_true) {
+        true
+    } else {
+        yansi::Condition::stderr_is_tty()
+    }
+}
@@ -0,0 +1,142 @@
+//! Helpers for the calls `expression.unwrap()` and `expression.expect(message)` that are replaced
+//! with the `unwrap` argument of the attribute. Those print the Result or Option that could not be
+//! unwrapped, just like `let_assert!` does.
+
+use std::fmt::Debug;
+
+/// A Result or Option, whose `unwrap()` and `expect(message)` calls we can replace. If this trait is
+/// not implemented for the receiver of such a call, then the receiver is not a Result or Option and
+/// the `unwrap` argument of the attribute can't be used for the function.
+pub trait Unwrap {
+    /// the value inside of `Ok` or `Some`
+    type Value;
+    /// the value that could not be unwrapped, which is `Err(error)` or `None`
+    type Failure: Debug;
+
+    /// the value inside of `Ok` or `Some` or the reason why there is none
+    fn __assert2ify_unwrap(self) -> Result<Self::Value, UnwrapFailure<Self::Failure>>;
+}
+
+/// A Result or Option that could not be unwrapped
+pub struct UnwrapFailure<Failure> {
+    /// the value that could not be unwrapped, like `Err(error)` or `None`
+    pub value: Failure,
+    /// the pattern that the value did not match, like `Ok(_)`
+    pub pattern: &'static str,
+    /// the debug output of the error of a Result or None for an Option
+    error: Option<String>,
+}
+
+impl<Failure> UnwrapFailure<Failure> {
+    /// the message of the panic, which is the same as the one of the standard library
+    /// # Arguments
+    /// * `message`: the message given to `expect(message)` or None for `unwrap()`
+    pub fn panic_message(&self, message: Option<&str>) -> String {
+        match (message, &self.error) {
+            (None, Some(error)) => {
+                format!("called `Result::unwrap()` on an `Err` value: {}", error)
+            }
+            (None, None) => String::from("called `Option::unwrap()` on a `None` value"),
+            (Some(message), Some(error)) => format!("{}: {}", message, error),
+            (Some(message), None) => message.to_string(),
+        }
+    }
+}
+
+impl<T, E: Debug> Unwrap for Result<T, E> {
+    type Value = T;
+    type Failure = Result<(), E>;
+
+    fn __assert2ify_unwrap(self) -> Result<T, UnwrapFailure<Result<(), E>>> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(UnwrapFailure {
+                error: Some(format!("{:?}", error)),
+                value: Err(error),
+                pattern: "Ok(_)",
+            }),
+        }
+    }
+}
+
+impl<T> Unwrap for Option<T> {
+    type Value = T;
+    type Failure = Option<()>;
+
+    fn __assert2ify_unwrap(self) -> Result<T, UnwrapFailure<Option<()>>> {
+        match self {
+            Some(value) => Ok(value),
+            None => Err(UnwrapFailure {
+                value: None,
+                pattern: "Some(_)",
+                error: None,
+            }),
+        }
+    }
+}
+
+/// `reference.unwrap()` copies the Result if it is Copy
+impl<T: Copy, E: Copy + Debug> Unwrap for &Result<T, E> {
+    type Value = T;
+    type Failure = Result<(), E>;
+
+    fn __assert2ify_unwrap(self) -> Result<T, UnwrapFailure<Result<(), E>>> {
+        (*self).__assert2ify_unwrap()
+    }
+}
+
+/// `reference.unwrap()` copies the Option if it is Copy
+impl<T: Copy> Unwrap for &Option<T> {
+    type Value = T;
+    type Failure = Option<()>;
+
+    fn __assert2ify_unwrap(self) -> Result<T, UnwrapFailure<Option<()>>> {
+        (*self).__assert2ify_unwrap()
+    }
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// The replacement for `expression.unwrap()` and `expression.expect(message)`, which evaluates to
+/// the value inside of `Ok` or `Some`. Otherwise, the Result or Option is printed like a failed
+/// `let_assert!` and we panic with the same message as the standard library.
+macro_rules! __unwrap {
+    ($expression:expr) => {
+        match $crate::assertions::unwrap::Unwrap::__assert2ify_unwrap($expression) {
+            Ok(value) => value,
+            Err(failure) => $crate::__unwrap_failure!(failure, $expression, None, []),
+        }
+    };
+    ($expression:expr, $message:expr $(,)?) => {
+        match (
+            $crate::assertions::unwrap::Unwrap::__assert2ify_unwrap($expression),
+            $message,
+        ) {
+            (Ok(value), _) => value,
+            (Err(failure), message) => {
+                let message: &str = message;
+                $crate::__unwrap_failure!(failure, $expression, Some(message), ["{}", message])
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// helper that prints the failure of an unwrapped expression and panics with the panic message
+/// of the standard library
+macro_rules! __unwrap_failure {
+    ($failure:ident, $expression:expr, $message:expr, [$($info_args:tt)*]) => {{
+        $crate::__print_failure!(
+            "let_assert",
+            [$($info_args)*],
+            $crate::assertions::print::MatchExpr {
+                print_let: false,
+                value: &$failure.value,
+                pattern: $failure.pattern,
+                expression: ::std::stringify!($expression),
+            }
+        );
+        ::std::panic!("{}", $failure.panic_message($message))
+    }};
+}
@@ -26,7 +26,8 @@
 //! ```
 //!
 //! You can annotate any function (usually a test case) with `#[assert2ify]` and it
-//! takes care of replacing the assertions inside the functions.
+//! takes care of replacing the assertions inside the functions. You can also annotate a whole
+//! module (e.g. your `#[cfg(test)] mod tests`) and have the assertions in all of its functions replaced.
 //!
 //! # Usage
 //! Just annotate any function with the `#[assert2ify]` attribute and have the attribute
@@ -41,6 +42,16 @@
 //!
 //! See what more the [assert2ify](assert2ify) attribute can do for you in the documentation. The docs
 //! also go into detail about the limitations of the attribute.
+//!
+//! # Approximate Comparisons
+//! Comparing floating point numbers with `assert_eq!` is a bug waiting to happen. That's why this
+//! crate also offers the [assert_approx_eq!] macro, which compares two numbers with an absolute,
+//! relative or ULP based tolerance, e.g. `assert_approx_eq!(a, b, abs = 1e-9)`. If it fails, both
+//! numbers, their difference and the tolerance are printed. There are [check_approx_eq!],
+//! [debug_assert_approx_eq!] and [debug_check_approx_eq!] variants, too.
+
+// the examples in the documentation show test functions on purpose
+#![allow(clippy::test_attr_in_doctest)]
 
 #[doc(hidden)]
 pub mod assertions;
@@ -54,6 +65,40 @@ pub mod assertions;
 /// annotated `#[test]`, but it is possible to use with any function. See below for examples what
 /// exactly is replaced and how to use the macro
 ///
+/// ## Modules
+/// Instead of annotating every single test, you can also stick the attribute above an inline module.
+/// Then the assertions in all functions, nested modules, impl blocks and closures inside the module
+/// are replaced, using the same arguments (see below) throughout.
+/// ```rust
+/// # use assert2ify::assert2ify;
+/// #[cfg(test)]
+/// #[assert2ify(check)]
+/// mod tests {
+///     #[test]
+///     fn my_test() {
+///         assert_eq!(1 + 1, 2);
+///     }
+/// }
+/// ```
+/// Modules that live in their own file (`mod tests;`) can't be annotated.
+///
+/// ## Impl Blocks and Traits
+/// The attribute can also be applied to `impl` blocks and trait definitions, as well as to single
+/// methods inside of them. This is handy for test fixtures with helper methods that contain assertions.
+/// ```rust
+/// # use assert2ify::assert2ify;
+/// struct Fixture(Vec<i32>);
+///
+/// #[assert2ify]
+/// impl Fixture {
+///     fn verify_len(&self, len: usize) {
+///         assert_eq!(self.0.len(), len);
+///     }
+/// }
+/// # Fixture(vec![1,2]).verify_len(2);
+/// ```
+/// Other items such as structs or enums can't be annotated.
+///
 /// ## Arguments
 ///
 /// ### check
@@ -63,8 +108,37 @@ pub mod assertions;
 /// `check!` macro only fails at the end of the test. This is useful if you want to see every
 /// failing assertion inside a test inside a single pass.
 ///
-/// **Caveat**: `assert!(matches!(...))` expressions will still be replaced by assertions because
-/// there is no `let_check!` in assert2 as it would not make sense.
+/// This is also true for `assert!(matches!(...))` expressions, which are replaced by a check of
+/// the pattern that prints the value of the expression if it does not match.
+///
+/// ### unwrap
+/// A failing `unwrap()` only tells you that it was called on an `Err` or `None` value, and the
+/// location is often inside of some helper function. With `#[assert2ify(unwrap)]`, the calls
+/// `expression.unwrap()` and `expression.expect(message)` on a Result or Option are replaced
+/// by an expression that prints the failure just like `let_assert!(Ok(_) = expression)` does,
+/// together with the source text and location of the call. The panic message stays the same as
+/// before, so `#[should_panic(expected = ...)]` keeps working. Since there is no value to continue
+/// with, this panics right away, even for `#[assert2ify(check, unwrap)]`.
+/// ```rust
+/// # use assert2ify::assert2ify;
+/// #[assert2ify(unwrap)]
+/// fn read_port() -> u16 {
+///     let port: u16 = "8080".parse().unwrap();
+///     port
+/// }
+/// # assert_eq!(read_port(), 8080);
+/// ```
+/// Calls to methods with the same names on other types than Result and Option don't compile with
+/// this argument, so it's not enabled by default.
+///
+/// ### panics
+/// Tests often assert with code that panics unless some condition holds, like
+/// `if x != y { panic!(...) }`. With `#[assert2ify(panics)]`, these idioms are replaced by
+/// assertions, too. The same goes for matches, `if let` and `let ... else` that panic unless
+/// a value matches a pattern, see below. The panic message stays the same as before, so
+/// `#[should_panic(expected = ...)]` keeps working. The argument can be combined with the
+/// others, e.g. `#[assert2ify(check, panics)]`. Code that panics on purpose is not meant
+/// to be an assertion, which is why this is not enabled by default.
 ///
 /// ### crate = ...
 /// In case you felt the need to rename this crate in your cargo toml, the compiler will get confused
@@ -102,6 +176,20 @@ pub mod assertions;
 /// # }
 /// ```
 ///
+/// The assertions are also recognized when they are given with qualified paths such as
+/// `std::assert!`, `::core::assert_eq!` or `std::prelude::v1::assert_ne!`.
+///
+/// ## Conjunctions
+/// Assertions on a chain of conjunctions like `assert!(v.len() == 3 && v[0] > 0)` are split into one
+/// assertion per conjunct, so that a failure tells you exactly which part of the condition was violated.
+/// The conjuncts are checked in order and a conjunct is only evaluated if all the conjuncts before it
+/// held, just like with `&&`. This also works for `matches!(...)` conjuncts.
+///
+/// ## Debug Assertions
+/// The debug assertions `debug_assert!`, `debug_assert_eq!` and `debug_assert_ne!` are replaced as well.
+/// They are only checked if debug assertions are enabled, so release builds behave exactly as before.
+/// This works with both the assert and the check style.
+///
 /// ## `assert!(matches!(...))` Expressions
 /// These statements are e.g. used when pattern matching on `Ok` or `Err` variants in unit tests.
 /// Unfortunately they are pretty useless, because it would be nice to see not only _that_ the match failed
@@ -115,14 +203,111 @@ pub mod assertions;
 /// }
 /// assert!(matches!(foo(-1),Err(s) if s.contains("negative value")));
 /// ```
-/// then the corresponding assertion will be replaced by
+/// then the corresponding assertion behaves like
 /// ```
 /// # fn foo(i : i32) -> Result<i32,String> {Err(String::from("negative value!"))}
 /// assert2::let_assert!(Err(s) = foo(-1));
 /// assert2::assert!(s.contains("negative value"));
 /// ```
 /// We have to make this two assertions because as of yet, `let_assert!` does not support additional
-/// if statements. This will give you infinitely more helpful messages in case of panics.
+/// if statements. This will give you infinitely more helpful messages in case of panics. If the if
+/// statement fails, the message also lists the values of the variables bound by the pattern
+/// (here: `s`). Additional info arguments (see below) are printed for both failures. Unlike with
+/// `let_assert!`, the value is matched in place, so the assertion does not move it.
+///
+/// In the check style, the pattern is checked without panicking. The if statement is only checked
+/// if the pattern matches, so it can use the variables bound by the pattern just like before.
+///
+/// The negation `assert!(!matches!(state, State::Error(_)))` fails if the value _does_ match the
+/// pattern (and the if statement, if there is one). In that case the value that matched is printed.
+/// If the pattern binds variables, the value might have been moved into them, which is why the
+/// values of those variables are printed instead. This works in both the assert and the check style.
+///
+/// ## `is_ok()`, `is_err()`, `is_some()` and `is_none()`
+/// An assertion like `assert!(result.is_ok())` will only tell you that the method returned `false`.
+/// That's why these four method calls are replaced by a check that prints the actual value in case
+/// of a failure, as if you had written `assert2::let_assert!(Ok(_) = result)`. So for the assertion
+/// above you'd see e.g. that `result` was `Err("timeout")`. Unlike `let_assert!`, the value is
+/// not moved, so it can still be used after the assertion.
+///
+/// ## `contains`, `starts_with`, `ends_with` and `is_match`
+/// Assertions on these methods, such as `assert!(log.contains("connected"))`, are replaced by checks
+/// that print the values of both the receiver (here: `log`) and the argument in case of a failure.
+/// If the receiver is a long string, the output also points to the part of it that comes closest to
+/// satisfying the assertion. Arguments that are not `Copy` are moved into the method call, which
+/// is why only their expression is printed. Closure arguments, like in `s.contains(|c| c == 'x')`,
+/// are left to the plain assertion of assert2.
+///
+/// ## Comparing Sequences
+/// When `assert_eq!` fails for two long sequences, such as two `Vec`s with hundreds of elements,
+/// we'd have to compare both of them by eye to find the difference. That's why a failing
+/// `assert_eq!` on slices, arrays, `Vec`s and `VecDeque`s prints the first index at which the
+/// sequences differ, whether their lengths differ and the elements around the difference instead.
+/// For all other types, the output is the same as that of assert2.
+///
+/// ## Comparing Bytes
+/// If both sides of an `assert_eq!` are bytes, like `&[u8]`, `Vec<u8>`, `[u8; N]` or a type that
+/// dereferences to `[u8]` like the `Bytes` type of the bytes crate, the output is a hex dump of the
+/// rows that differ, together with their offset and the ASCII representation of the bytes.
+/// The differing bytes are highlighted.
+///
+/// ## Comparing Multi-Line Strings
+/// A failing `assert_eq!` on two strings, at least one of which has multiple lines, prints a
+/// unified, line based diff of the strings instead of the strings themselves. This is handy for tests
+/// that compare generated text with its expected output. Differences that would otherwise be
+/// invisible are marked in the changed lines: trailing spaces (`·`), tabs (`→`), Windows line
+/// endings (`␍`), a missing newline at the end and invisible Unicode characters like zero width spaces
+/// (e.g. `\u{200b}`). Just like the output of assert2, the diff is only colored if the `ASSERT2` and
+/// `CLICOLOR` environment variables and the terminal allow it.
+///
+/// ## Comparing Structs and Enums
+/// A failing `assert_eq!` on two big structs or enums would print two giant debug dumps. Instead,
+/// only the fields that differ are printed together with their path, like `server.tls.cert_path`.
+/// The difference is found in the pretty debug output (`{:#?}`) of the values, so this works
+/// for all types that implement `Debug`, including nested structs, enums, tuples and collections.
+/// Small values like `Some(1)` or `(1, "a")`, whose debug output fits in a single line, are
+/// printed as they are.
+///
+/// ## Comparing Maps and Sets
+/// The iteration order of a `HashMap` or `HashSet` is arbitrary, so two dumps of a map that differs
+/// in a single key are hard to compare. If both sides of an `assert_eq!` are a `HashMap`,
+/// `BTreeMap`, `HashSet` or `BTreeSet`, the output lists the keys that only the left or only the right
+/// side has and the keys with different values. The keys are sorted if they implement `Ord` and
+/// sorted by their debug output otherwise, so the output is the same from run to run.
+///
+/// ## Absolute Differences
+/// Assertions on the idiom `assert!((a - b).abs() < epsilon)` only tell you the difference, but
+/// not the numbers that were compared. That's why comparisons of an absolute difference
+/// `(a - b).abs()` with `<`, `<=`, `>` or `>=` (on either side) are replaced by checks that print
+/// `a` and `b`, too. The comparison stays exactly as it was written, so use [assert_approx_eq!]
+/// directly if you want tolerances that are relative or given in ULPs.
+///
+/// ## `len()` and `is_empty()`
+/// Assertions like `assert!(v.is_empty())` or `assert_eq!(v.len(), 3)` only tell you that the
+/// vector was not empty or that it had a different length. That's why assertions on `is_empty()`
+/// and comparisons with `len()` (on either side) are replaced by checks that also print the contents of the
+/// collection in case of a failure. The output is truncated for large collections. This works for
+/// `assert!`, `assert_eq!` and `assert_ne!` and their debug counterparts.
+///
+/// ## `all` and `any`
+/// Assertions like `assert!(values.iter().all(|v| *v >= 0))` are replaced by checks that loop over
+/// the elements and print the index and the value of the first element that does not satisfy the
+/// closure. For `any`, all elements of the iterator are printed instead. The closure is called
+/// exactly as often as before, so closures with side effects behave just like they did. Elements
+/// that are not `Copy` are moved into the closure, which is why only their index or count is printed.
+/// Receivers that are not iterators, but have `all` or `any` methods of their own (like the
+/// parallel iterators of rayon), are checked like any other boolean expression. The same goes
+/// for receivers that are variables or fields, like `iterator.all(...)`, which must not be moved.
+///
+/// ## Comparisons With `true` and `false`
+/// Assertions like `assert_eq!(matches!(v, Some(_)), true)` or `assert_eq!(v.is_empty(), false)`
+/// would only tell you that `false` is not `true`. That's why comparisons with a boolean literal
+/// in `assert_eq!` and `assert_ne!` (on either side) are replaced by an assertion on the other side
+/// or on its negation, which also gets the treatment of `matches!`, `is_empty()`, etc.
+/// This is only done if the other side is certainly a `bool`, which is the case for `matches!`,
+/// comparisons, `&&` and `||`, the methods listed above like `is_some()` or `contains(x)` and
+/// negations of those. Everything else, like `assert_eq!(is_valid(), true)`, stays a comparison,
+/// because it might be some other type that can be compared with a `bool`.
 ///
 /// ## Additional Arguments to the Assertions
 /// A format string and all addtional variadic arguments to the assertions are handled as you would expect
@@ -130,23 +315,67 @@ pub mod assertions;
 /// `assert!(j>5,"wrong number of messages")` will be replaced by an assertion that also carries
 /// the additional info.
 ///
+/// ## `if condition { panic!(...) }`
+/// With the `panics` argument, the idiom `if x != y { panic!("mismatch: {} vs {}", x, y) }` is
+/// replaced by an assertion that the condition is false, so it benefits from the expansion output
+/// and from the `check` style, too. This works for statements that are an `if` without an `else`,
+/// whose body is nothing but a `panic!` with no arguments or with a format string. The arguments
+/// of the panic become the additional info of the assertion. If the assertion fails, it panics
+/// with the original `panic!`, which evaluates its arguments a second time.
+/// The negation is pushed into the condition where that does not change its meaning, so
+/// `if x != y` becomes an assertion on `x == y` and `if a || b` becomes an assertion on `!a && !b`.
+///
+/// ## Patterns With Diverging Alternatives
+/// With the `panics` argument, the idioms
+/// `match result { Ok(v) => v, Err(e) => panic!("unexpected {:?}", e) }`,
+/// `if let Some(x) = option { ... } else { unreachable!() }` and
+/// `let Some(x) = option else { panic!() };` are replaced by code that binds the same variables
+/// and prints the value that did not match the pattern just like `let_assert!` does. The value is
+/// matched in place, so it is only moved if the original code moves it. For matches, all arms but one must
+/// consist of nothing but a `panic!` or `unreachable!`. The arm that does not diverge must not have
+/// a guard, and the arms before it may only match other enum variants (like `None` before
+/// `Some(v)`), so that the pattern alone decides whether the code diverges. The message of the
+/// diverging macro is printed, unless it might refer to variables bound by a diverging arm or
+/// there are several diverging arms. After that, the original diverging code runs, so it panics
+/// right away with the same message as before, even in the `check` style.
+///
 /// # Limitations and Caveats
 /// The crate traverses the syntax tree given by the contents of the function and replaces the occurrences
 /// of standard library assertions with the assertions of the assert2 crate. For most of the uses
 /// cases this will be fine, since assertions inside loops, closures, if-statements, etc. will be
-/// replaced
+/// replaced. This is also true for assertions nested inside other assertions, e.g. in a closure
+/// like `assert!(items.iter().all(|i| { assert_ne!(i.id, 0); i.valid }))`.
 ///
 /// ## Assertions in Nested Code
 /// However, there are some edge cases where replacing will not occur. In these cases
 /// the standard library assertions will be left untouched and the test will behave as it did previously.
 ///
-/// ### Assertions Inside Assertions
-/// Assertions inside assertions are not replaced. Only the outer assertion will be replaced while the
-/// other one is left as it was. I personally think that assertions inside assertions indicate
-/// a flawed test design and probably won't be working to fix this issue.
-///
 /// ### Assertions Inside Macros
-/// Assertions inside macros invocations (and definitions) are only replaced if the tokens inside
-/// the macros can be parsed as an expression. This does not cover all possible cases and I will
-/// work to change this in future versions of this crate.
+/// Assertions inside macro invocations such as `vec![...]`, `format!(...)` or macros that take a list
+/// of statements are replaced, too. If the tokens inside the macro cannot be parsed as an expression,
+/// the attribute walks the raw tokens and replaces the assertions it finds in there, leaving everything
+/// else unchanged. However, the arguments of an assertion must still be valid expressions for the
+/// assertion to be replaced.
+///
+/// ### Assertions Inside Macro Definitions
+/// Assertions inside the rules of a `macro_rules!` definition that is declared inside the annotated
+/// function are replaced as well, so that every expansion of the macro uses the assertions of assert2.
+/// Metavariables like `$a` can be used inside the assertions, but assertions containing repetitions
+/// like `$($a),*` in their arguments are left untouched. So are macros invoked through the path
+/// `$crate::...`, like `$crate::assert!`, because they are not the ones of the standard library.
+/// ```rust
+/// # use assert2ify::assert2ify;
+/// # fn square(x: i32) -> i32 { x * x }
+/// #[assert2ify]
+/// fn table_driven_test() {
+///     macro_rules! case {
+///         ($a:expr, $b:expr) => {
+///             assert_eq!(square($a), $b)
+///         };
+///     }
+///     case!(2, 4);
+///     case!(-3, 9);
+/// }
+/// # table_driven_test();
+/// ```
 pub use assert2ify_macros::assert2ify;
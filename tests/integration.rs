@@ -1,6 +1,11 @@
+#![allow(clippy::useless_vec)]
+
 use assert2ify::assert2ify;
+// makes the assertion available as `$crate::assert!` for the macro definitions below
+use std::assert;
 
 mod logic;
+mod output;
 
 #[test]
 #[assert2ify(check, crate = assert2ify)]
@@ -40,15 +45,187 @@ fn error() -> Result<i32, String> {
     Err("bla".to_string())
 }
 
+#[test]
+fn approximate_equality_within_any_tolerance_succeeds() {
+    assert2ify::assert_approx_eq!(0.1 + 0.2, 0.3);
+    assert2ify::assert_approx_eq!(1.0f32, 1.001, abs = 0.01);
+    assert2ify::assert_approx_eq!(1000.0, 1000.1, rel = 1e-3, "{}", "close enough");
+    assert2ify::assert_approx_eq!(1.0, 1.0 + 2.0 * f64::EPSILON, ulps = 2);
+    assert2ify::assert_approx_eq!(1.0, 2.0, abs = 0.1, rel = 0.6);
+    assert2ify::assert_approx_eq!(-0.0, 0.0, ulps = 0);
+    assert2ify::assert_approx_eq!(f64::INFINITY, f64::INFINITY);
+    let (left, right) = (&1.5, &1.5);
+    assert2ify::assert_approx_eq!(left, right, abs = 0);
+    assert2ify::check_approx_eq!(left, right);
+    assert2ify::debug_assert_approx_eq!(0.1 + 0.2, 0.3);
+    assert2ify::debug_check_approx_eq!(0.1 + 0.2, 0.3, abs = 1e-12,);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed")]
+fn approximate_equality_outside_of_the_tolerance_fails() {
+    assert2ify::assert_approx_eq!(1.0, 1.1, abs = 0.01, rel = 0.01, ulps = 4);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed")]
+fn approximate_equality_fails_for_numbers_that_differ_by_more_than_4_ulps_by_default() {
+    assert2ify::assert_approx_eq!(1.0f32, 1.0 + 5.0 * f32::EPSILON);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed")]
+fn approximate_equality_fails_for_nan() {
+    assert2ify::assert_approx_eq!(f64::NAN, f64::NAN, abs = f64::INFINITY);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed")]
+fn approximate_equality_fails_for_different_infinities() {
+    assert2ify::assert_approx_eq!(f64::MAX, f64::INFINITY, rel = 1.0);
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "assertion failed"))]
+fn debug_approximate_equality_is_only_checked_with_debug_assertions() {
+    assert2ify::debug_assert_approx_eq!(1.0, 2.0, "{}", "only in debug mode");
+}
+
+#[assert2ify(panics)]
+#[test]
+#[should_panic(expected = "mismatch: 1 vs 2")]
+fn if_panic_idioms_keep_the_panic_message() {
+    let (left, right) = (1, 2);
+    if left != right {
+        panic!("mismatch: {} vs {}", left, right);
+    }
+}
+
+#[assert2ify(panics)]
+#[test]
+#[should_panic(expected = "explicit panic")]
+fn if_panic_idioms_keep_the_message_of_an_explicit_panic() {
+    if vec![1, 2].len() > 1 || vec![3].is_empty() {
+        panic!()
+    }
+}
+
+#[assert2ify(check, panics)]
+#[test]
+#[should_panic(expected = "unexpected ParseIntError { kind: InvalidDigit }")]
+fn diverging_pattern_idioms_panic_right_away_even_for_checks() {
+    let port = match parse_port("http") {
+        Ok(port) => port,
+        Err(error) => panic!("unexpected {:?}", error),
+    };
+    panic!("unreachable, because there is no port {}", port);
+}
+
+#[assert2ify(panics)]
+#[test]
+#[should_panic(expected = "no port")]
+fn let_else_is_replaced_by_let_assert() {
+    let Some(port) = parse_port("http").ok() else {
+        panic!("no port");
+    };
+    panic!("unreachable, because there is no port {}", port);
+}
+
+#[assert2ify(panics)]
+#[test]
+#[should_panic(expected = "internal error: entered unreachable code")]
+fn diverging_pattern_idioms_keep_the_message_of_unreachable() {
+    let port = if let Ok(port) = parse_port("http") {
+        port
+    } else {
+        unreachable!()
+    };
+    panic!("unreachable, because there is no port {}", port);
+}
+
 #[assert2ify(check)]
 #[test]
-#[should_panic]
-// there is no check(let...) so even if checkification is enabled, then
-// assert!(matches!(...)) is converted to assert2::assert(let ...))
-// and fails immediately
-fn test_that_checkification_replaces_assert_matches_with_assertions_instead() {
-    assert!(matches!(something(), Err(_)), "something {}", "is wrong");
-    panic!("this panic should never be reached, even for checkification");
+#[should_panic(expected = "the negative pattern checks do not panic right away")]
+fn checkification_of_negative_patterns_does_not_immediately_panic() {
+    let result: Result<i32, String> = Err(String::from("bad"));
+    assert!(!matches!(result, Err(_)), "{}", "failed");
+    assert!(!matches!(result, Err(ref message) if message.len() == 3));
+    panic!("the negative pattern checks do not panic right away");
+}
+
+/// parse a port number like the helpers that tests unwrap all the time
+fn parse_port(port: &str) -> Result<u16, std::num::ParseIntError> {
+    port.parse()
+}
+
+#[assert2ify(unwrap)]
+#[test]
+fn unwrapping_gives_the_values_of_results_and_options() {
+    let port = parse_port("8080").unwrap();
+    assert_eq!(port, 8080);
+    let ports = vec![80, 443];
+    assert_eq!(ports.first().unwrap(), &80);
+    let first = Some(1);
+    let reference = &first;
+    assert_eq!(reference.unwrap(), 1);
+    let message = String::from("the port must be a number");
+    assert_eq!(parse_port("22").expect(&message), 22);
+    let nested: Option<Result<&str, ()>> = Some(Ok("443"));
+    assert_eq!(parse_port(nested.unwrap().unwrap()).unwrap(), 443);
+    // methods with other names or arguments are left alone
+    assert_eq!(parse_port("x").unwrap_or(1), 1);
+    // first is still usable, because it was copied
+    assert!(first.is_some());
+}
+
+#[assert2ify(unwrap)]
+#[test]
+#[should_panic(
+    expected = "called `Result::unwrap()` on an `Err` value: ParseIntError { kind: InvalidDigit }"
+)]
+fn unwrapping_an_error_panics_with_the_message_of_the_standard_library() {
+    let _port = parse_port("http").unwrap();
+}
+
+#[assert2ify(unwrap)]
+#[test]
+#[should_panic(expected = "called `Option::unwrap()` on a `None` value")]
+fn unwrapping_none_panics_with_the_message_of_the_standard_library() {
+    let ports: Vec<u16> = Vec::new();
+    assert_eq!(ports.first().unwrap(), &80);
+}
+
+#[assert2ify(unwrap)]
+#[test]
+#[should_panic(expected = "the port must be a number: ParseIntError { kind: InvalidDigit }")]
+fn expecting_an_error_panics_with_the_given_message() {
+    let _port = parse_port("http").expect("the port must be a number");
+}
+
+#[assert2ify(check, unwrap)]
+#[test]
+#[should_panic(expected = "no port configured")]
+fn unwrapping_panics_right_away_even_for_checks() {
+    let port: Option<u16> = None;
+    port.expect("no port configured");
+    panic!("unwrapping did not panic right away");
+}
+
+#[assert2ify]
+#[test]
+#[should_panic(expected = "assertion failed")]
+fn assertification_of_conjunctions_short_circuits() {
+    let v: Vec<i32> = Vec::new();
+    assert!(!v.is_empty() && v[0] == 1);
+}
+
+#[assert2ify(check)]
+#[test]
+#[should_panic(expected = "check failed")]
+fn checkification_of_assert_matches_does_not_move_out_of_the_expression_on_mismatch() {
+    let result = error();
+    assert!(matches!(result, Ok(_)));
+    assert_eq!(result.unwrap_err(), "bla");
 }
 
 test_all_assertification_styles! {
@@ -66,6 +243,72 @@ test_all_assertification_styles! {
     }
 }
 
+test_all_assertification_styles! {
+    #[test]
+    fn matches_assertions_accept_info_arguments_made_of_multiple_tokens() {
+        let a = 1;
+        assert!(matches!(something(), Ok(i) if i < 2000), "bad {} and {}", a + 1, vec![a].len());
+        assert!(matches!(error(), Err(s) if s == "bla",), "bad {}", a + 2,);
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    #[should_panic]
+    fn matches_assertions_with_multiple_token_info_arguments_still_fail() {
+        let a = 1;
+        assert!(matches!(error(), Ok(1337)), "bad {}", a + 2);
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn matches_assertions_accept_patterns_with_a_leading_vert() {
+        let value = Some(1);
+        assert!(matches!(value, | Some(_) | None));
+        assert!(matches!(value, | Some(1) | Some(2) if value.is_some(),), "{}", "bad");
+        assert!(!matches!(value, | Some(2) | None));
+        assert!(value.is_some() && matches!(value, | Some(1)));
+        // the value is matched in place and not moved
+        let name = Some(String::from("name"));
+        assert!(matches!(name, Some(_) | None));
+        drop(name);
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    #[should_panic]
+    fn matches_assertions_with_a_leading_vert_still_fail() {
+        assert!(matches!(Some(2), | Some(1) | None));
+    }
+}
+
+struct NotDebug(i32);
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+test_all_assertification_styles! {
+    #[test]
+    #[should_panic]
+    fn failing_guards_may_use_bindings_that_do_not_implement_debug() {
+        let value = Some(NotDebug(1));
+        assert!(matches!(value, Some(NotDebug(inner)) if inner > 1), "bad {}", "value");
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    #[should_panic]
+    fn failing_guards_may_use_bindings_of_nested_patterns() {
+        let point = Point { x: 1, y: 2 };
+        assert!(matches!(point, Point { x, y: ref other } if x == *other), "{}", "not on the diagonal");
+    }
+}
+
 test_all_assertification_styles! {
     #[test]
     #[should_panic]
@@ -84,14 +327,12 @@ fn checkification_works_for_nested_assertion_in_expression_inside_macro() {
     identity!(identity!(identity!(assert_eq!(2, 3))));
 }
 
-// this assertion will not be replaced, because my macro parsing
-// only works when a macro contains an expression. But we make sure
-// that the original assertion is carried through and the test still
-// fails as expected.
+// the tokens inside these macros can't be parsed as an expression, so
+// the assertion is found by walking the tokens
 test_all_assertification_styles! {
     #[test]
     #[should_panic]
-    fn even_if_nested_assertions_inside_macros_are_not_replaced_they_still_fail_as_expected() {
+    fn nested_assertion_in_statements_inside_macro_is_replaced() {
         identity! {
             identity!{
                 identity!{
@@ -100,3 +341,263 @@ test_all_assertification_styles! {
         ;
     }
 }
+
+#[assert2ify(check)]
+mod assertions_inside_an_annotated_module {
+    struct Fixture(Vec<i32>);
+
+    impl Fixture {
+        fn verify_len(&self, len: usize) {
+            assert_eq!(self.0.len(), len);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "check failed")]
+    fn are_replaced_in_functions() {
+        assert_eq!(1, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "check failed")]
+    fn are_replaced_in_impl_blocks() {
+        Fixture(vec![1, 2]).verify_len(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "check failed")]
+    fn are_replaced_in_closures() {
+        let verify = |x: i32| {
+            assert!(x > 10);
+        };
+        verify(5);
+    }
+
+    mod nested {
+        #[test]
+        #[should_panic(expected = "check failed")]
+        fn are_replaced_in_nested_modules() {
+            assert_ne!(1, 1);
+        }
+    }
+}
+
+struct Fixture {
+    values: Vec<i32>,
+}
+
+#[assert2ify(check)]
+impl Fixture {
+    fn verify_len(&self, len: usize) {
+        assert_eq!(self.values.len(), len);
+    }
+}
+
+#[assert2ify(check)]
+trait VerifyValues {
+    fn values(&self) -> &[i32];
+
+    fn verify_first(&self, first: i32) {
+        assert_eq!(self.values()[0], first);
+    }
+}
+
+impl VerifyValues for Fixture {
+    #[assert2ify(check)]
+    fn values(&self) -> &[i32] {
+        &self.values
+    }
+}
+
+trait ProvideValues {
+    #[assert2ify(check)]
+    fn provide(&self) -> Vec<i32>;
+}
+
+struct OtherFixture;
+
+impl ProvideValues for OtherFixture {
+    fn provide(&self) -> Vec<i32> {
+        vec![1, 2, 3]
+    }
+}
+
+impl OtherFixture {
+    #[assert2ify(check)]
+    fn verify_positive(&self, value: i32) {
+        assert!(value > 0);
+    }
+}
+
+#[test]
+#[should_panic(expected = "check failed")]
+fn assertions_in_annotated_impl_blocks_are_replaced() {
+    Fixture { values: vec![1] }.verify_len(2);
+}
+
+#[test]
+#[should_panic(expected = "check failed")]
+fn assertions_in_default_methods_of_annotated_traits_are_replaced() {
+    Fixture { values: vec![1] }.verify_first(2);
+}
+
+#[test]
+#[should_panic(expected = "check failed")]
+fn assertions_in_annotated_methods_are_replaced() {
+    OtherFixture.verify_positive(OtherFixture.provide()[0] - 2);
+}
+
+#[test]
+#[should_panic(
+    expected = "the debug assertion is replaced by a check that does not panic immediately"
+)]
+#[assert2ify(check)]
+fn checkification_replaces_debug_assertions_by_checks_that_do_not_immediately_panic() {
+    debug_assert!(1 > 2);
+    debug_assert_eq!(1, 2);
+    debug_assert_ne!(1, 1);
+    panic!("the debug assertion is replaced by a check that does not panic immediately");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "check failed")]
+#[assert2ify(check)]
+fn failing_debug_checks_still_fail_the_test() {
+    debug_assert_eq!(1, 2);
+}
+
+#[test]
+#[should_panic(expected = "the qualified assertions are replaced by checks")]
+#[assert2ify(check)]
+fn checkification_replaces_qualified_assertions() {
+    core::assert!(1 > 2);
+    ::core::assert_eq!(1, 2);
+    std::prelude::v1::assert_ne!(1, 1);
+    panic!("the qualified assertions are replaced by checks");
+}
+
+#[test]
+#[should_panic(expected = "check failed")]
+#[assert2ify(check)]
+fn assertions_nested_inside_unary_assertions_are_replaced() {
+    let ids = vec![1, 0, 2];
+    assert!(ids.iter().all(|id| {
+        assert_ne!(*id, 0);
+        true
+    }));
+}
+
+#[test]
+#[should_panic(expected = "check failed")]
+#[assert2ify(check)]
+fn assertions_nested_inside_binary_assertions_are_replaced() {
+    let ids = vec![1, 0, 2];
+    assert_eq!(
+        ids.iter()
+            .map(|id| {
+                assert!(*id > 0);
+                *id
+            })
+            .sum::<i32>(),
+        3
+    );
+}
+
+#[test]
+#[assert2ify(check)]
+#[should_panic(expected = "the assertion in the statements is replaced by a check")]
+fn checkification_works_for_nested_assertion_in_statements_inside_macro() {
+    identity! {
+        identity! {
+            let a = 1 + 1;
+            assert_eq!(a, 3);
+        }
+    }
+    panic!("the assertion in the statements is replaced by a check");
+}
+
+#[test]
+#[assert2ify(check)]
+#[should_panic(expected = "check failed")]
+fn checkification_works_for_assertions_inside_vec_macro() {
+    let _v = vec![1, 2, {
+        assert!(1 > 2);
+        3
+    }];
+}
+
+#[test]
+#[assert2ify(check)]
+#[should_panic(expected = "check failed")]
+fn checkification_works_for_assertions_inside_format_macro() {
+    let _s = format!("{} {}", 1, {
+        assert_ne!(1, 1);
+        2
+    });
+}
+
+fn square(x: i32) -> i32 {
+    x * x
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn assertions_in_local_macro_definitions_pass() {
+        macro_rules! case {
+            ($a:expr, $b:expr) => {
+                assert_eq!(square($a), $b)
+            };
+            ($a:expr) => {{
+                assert!(square($a) >= 0, "square of {} is negative", $a);
+                assert!(matches!(square($a), x if x >= 0));
+            }};
+        }
+        case!(2, 4);
+        case!(-3, 9);
+        case!(5);
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn crate_paths_in_local_macro_definitions_are_left_alone() {
+        macro_rules! case {
+            ($a:expr) => {
+                $crate::assert!(square($a) >= 0, "square of {} is negative", $a)
+            };
+        }
+        case!(2);
+        case!(-3);
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    #[should_panic]
+    fn assertions_in_local_macro_definitions_fail() {
+        macro_rules! case {
+            ($a:expr, $b:expr) => {
+                assert_eq!(square($a), $b)
+            };
+        }
+        case!(2, 5);
+    }
+}
+
+#[test]
+#[assert2ify(check)]
+#[should_panic(expected = "the assertions in the macro definition are replaced by checks")]
+fn checkification_replaces_assertions_in_local_macro_definitions() {
+    macro_rules! case {
+        ($a:expr, $b:expr) => {
+            assert_eq!(square($a), $b);
+        };
+        ($($values:expr),+) => {
+            $(assert_ne!(square($values), $values);)+
+        };
+    }
+    case!(2, 5);
+    case!(3, 1);
+    panic!("the assertions in the macro definition are replaced by checks");
+}
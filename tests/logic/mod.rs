@@ -5,6 +5,8 @@
 #![allow(clippy::eq_op)]
 #![allow(clippy::op_ref)]
 #![allow(clippy::assertions_on_constants)]
+#![allow(clippy::nonminimal_bool)]
+#![allow(clippy::redundant_pattern_matching)]
 #![allow(unused_attributes)]
 
 mod helper_macros;
@@ -12,10 +14,12 @@ mod helper_macros;
 /// one "normal variant", one with #[assert2ify] config
 /// and one with an #[assert2ify(check)] config
 /// All tests should either panic or none of them, so we save
-/// some manual labor
+/// some manual labor. Further arguments of the attribute, like `panics`,
+/// can be given as `@args(...)` before the test
 #[macro_export]
 macro_rules! test_all_assertification_styles {
     (
+        $(@args($($arg:ident),*))?
         #[test]
         $(#[$attr:meta])?
         fn $test_name:ident () {
@@ -29,14 +33,14 @@ macro_rules! test_all_assertification_styles {
                 $($body)+
             }
 
-            #[::assert2ify::assert2ify]
+            #[::assert2ify::assert2ify($($($arg),*)?)]
             #[test]
             $(#[$attr])?
             fn [< $test_name _with_assertification>] () {
                 $($body)+
             }
 
-            #[::assert2ify::assert2ify(check)]
+            #[::assert2ify::assert2ify(check $($(, $arg)*)?)]
             #[test]
             $(#[$attr])?
             fn [< $test_name _with_checkification>] () {
@@ -54,8 +58,9 @@ macro_rules! test_all_assertification_styles {
 
 /// create multiple (assertify, checkify, normal) version of a test that should panic
 macro_rules! test_should_panic {
-    ($test_name:ident, $body:expr) => {
+    ($(@args($($arg:ident),*))? $test_name:ident, $body:expr) => {
         test_all_assertification_styles! {
+            $(@args($($arg),*))?
             #[test]
             #[should_panic]
             fn $test_name () {
@@ -65,7 +70,7 @@ macro_rules! test_should_panic {
     };
 }
 
-#[derive(Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash)]
 struct I(i32);
 
 // a simple test case, which is copied from the assert2 test cases
@@ -106,6 +111,58 @@ test_all_assertification_styles! {
     }
 }
 
+/// a buffer that dereferences to its bytes like the `Bytes` type of the bytes crate
+#[derive(Debug, PartialEq)]
+struct Payload(Vec<u8>);
+
+impl std::ops::Deref for Payload {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// a message of the wire protocol with the given body
+fn frame(body: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0xca, 0xfe, body.len() as u8];
+    frame.extend_from_slice(body);
+    frame
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn byte_equality() {
+        let expected = frame(b"hello world, this is a longer message");
+        assert_eq!(frame(b"hello world, this is a longer message"), expected);
+        assert_eq!(&expected[..3], [0xca, 0xfe, 37], "{}", "header");
+        assert_eq!(&b"hello"[..], b"hello");
+        assert_eq!(Payload(expected.clone()), Payload(expected.clone()));
+        assert_ne!(frame(b"hello"), frame(b"world"));
+        debug_assert_eq!(expected, frame(b"hello world, this is a longer message"));
+        // the values were not moved by the assertions
+        drop(expected);
+    }
+}
+
+test_should_panic!(panic_bytes1, assert_eq!(b"hello"[..], b"world"[..]));
+test_should_panic!(
+    panic_bytes2,
+    assert_eq!(
+        frame(b"hello world, this is a longer message"),
+        frame(b"hello world, this is a longer massage"),
+        "{}",
+        "typo"
+    )
+);
+test_should_panic!(
+    panic_bytes3,
+    assert_eq!(frame(b"hello"), [0xca, 0xfe, 5, b'h', b'e', b'l', b'l'])
+);
+test_should_panic!(
+    panic_bytes4,
+    assert_eq!(Payload(frame(b"hello")), Payload(frame(b"hallo")))
+);
+
 test_should_panic!(panic_assert1, assert!(1 == 2));
 test_should_panic!(panic_assert2, assert!(1 == 2, "{}", "math broke"));
 test_should_panic!(panic_assert3, assert!(true && false));
@@ -167,3 +224,897 @@ test_all_assertification_styles! {
         drop(b);
     }
 }
+
+test_all_assertification_styles! {
+    #[test]
+    fn conjunctions() {
+        let v = vec![1, 2, 3];
+        let x = 5;
+        assert!(v.len() == 3 && !v.is_empty() && v[0] > 0);
+        assert!(v.len() == 3 && v[2] == 3, "{} {}", "logic", "broke");
+        assert!(matches!(v.first(), Some(&1)) && v.len() == 3);
+        assert!(v.len() == 3 && matches!(v.last(), Some(i) if *i == 3) && true);
+        // the bindings of the pattern must not leak into the other conjuncts
+        assert!(matches!(Some(1), Some(x) if x == 1) && x == 5);
+        debug_assert!(v.len() == 3 && v[1] == 2);
+    }
+}
+
+test_should_panic!(panic_conjunction1, assert!(1 == 1 && 1 == 2 && 2 == 2));
+test_should_panic!(
+    panic_conjunction2,
+    assert!(1 == 1 && 2 == 2 && 1 == 2, "{}", "math broke")
+);
+test_should_panic!(
+    panic_conjunction3,
+    assert!(matches!(Result::<i32, i32>::Err(10), Ok(_)) && true)
+);
+test_should_panic!(
+    panic_conjunction4,
+    assert!(true && matches!(Result::<i32, i32>::Ok(10), Ok(x) if x > 10))
+);
+
+/// a type that has an `is_ok` method but is neither an Option nor a Result
+struct Status(bool);
+
+impl Status {
+    fn is_ok(&self) -> bool {
+        self.0
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn variant_methods() {
+        let ok: Result<String, String> = Ok(String::from("ok"));
+        let err: Result<String, String> = Err(String::from("err"));
+        let some = Some(I(1));
+        let none: Option<I> = None;
+        assert!(ok.is_ok());
+        assert!(err.is_err(), "{}", "rust broke");
+        assert!(some.is_some());
+        assert!(none.is_none());
+        let ok_ref = &ok;
+        assert!(ok_ref.is_ok() && ok.as_ref().is_ok());
+        assert!(Status(true).is_ok());
+        debug_assert!(ok.is_ok());
+        // the values were not moved by the assertions
+        drop((ok, err, some, none));
+    }
+}
+
+test_should_panic!(
+    panic_variant_method1,
+    assert!(Result::<i32, i32>::Err(10).is_ok())
+);
+test_should_panic!(
+    panic_variant_method2,
+    assert!(Result::<i32, i32>::Ok(10).is_err(), "{}", "rust broke")
+);
+test_should_panic!(panic_variant_method3, assert!(Option::<I>::None.is_some()));
+test_should_panic!(panic_variant_method4, assert!(Some(I(1)).is_none()));
+test_should_panic!(panic_variant_method5, assert!(Status(false).is_ok()));
+
+/// a type that has an `is_match` method like a regex
+struct Prefix(&'static str);
+
+impl Prefix {
+    fn is_match(&self, text: &str) -> bool {
+        text.starts_with(self.0)
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn method_calls() {
+        let log = String::from("[info] connected to server\n[info] sending data");
+        let word = String::from("server");
+        let numbers = vec![1, 2, 3];
+        let set: std::collections::HashSet<I> = vec![I(1)].into_iter().collect();
+        assert!(log.contains("connected"));
+        assert!(log.contains(&word) && log.contains('\n'), "{}", "no server");
+        assert!(log.starts_with("[info]"));
+        assert!(log.ends_with(char::is_alphabetic));
+        assert!(numbers.contains(&2));
+        assert!(numbers.starts_with(&[1, 2]));
+        assert!(numbers.ends_with(&[3]));
+        assert!((1..10).contains(&5));
+        assert!(set.contains(&I(1)));
+        assert!(std::path::Path::new("/tmp/file").starts_with(std::path::PathBuf::from("/tmp")));
+        assert!(Prefix("[info]").is_match(&log));
+        debug_assert!(log.contains("data"));
+        // the values were not moved by the assertions
+        drop((log, word, numbers));
+    }
+}
+
+test_should_panic!(
+    panic_method_call1,
+    assert!("a long log message that says we are connected to the server".contains("connection"))
+);
+test_should_panic!(
+    panic_method_call2,
+    assert!(String::from("hello").starts_with('w'), "{}", "no world")
+);
+test_should_panic!(panic_method_call3, assert!("hello".ends_with("world")));
+test_should_panic!(panic_method_call4, assert!(vec![I(1)].contains(&I(2))));
+test_should_panic!(
+    panic_method_call5,
+    assert!(std::path::Path::new("/tmp").starts_with(std::path::PathBuf::from("/usr")))
+);
+test_should_panic!(
+    panic_method_call6,
+    assert!(Prefix("[info]").is_match("[warn]"))
+);
+
+/// a type whose `contains` method takes a closure, which gets its parameter type from the method
+struct Bits(u8);
+
+impl Bits {
+    fn contains(&self, predicate: impl Fn(u8) -> bool) -> bool {
+        (0..8).any(|index| self.0 & (1 << index) != 0 && predicate(index))
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn method_calls_with_closures() {
+        let log = String::from("[info] connected");
+        let mut calls = Vec::new();
+        assert!(log.contains(|c: char| c.is_ascii_digit() || c == ']'));
+        assert!(log.starts_with(|c| c < 'a') && "abc".ends_with(|c| c > 'b'));
+        assert!(Bits(0b100).contains(|index| index.is_power_of_two()));
+        // the receiver is evaluated before the argument, just like in the method call
+        assert!({ calls.push("receiver"); log.as_str() }.contains({ calls.push("argument"); "info" }));
+        std::assert_eq!(calls, ["receiver", "argument"]);
+    }
+}
+
+test_should_panic!(panic_method_call7, assert!("hello".contains(|c| c > 'x')));
+test_should_panic!(
+    panic_method_call8,
+    assert!(Bits(0b1001).contains(|index| index.is_power_of_two()))
+);
+
+test_all_assertification_styles! {
+    #[test]
+    fn sequence_equality() {
+        let values: Vec<u32> = (0..500).collect();
+        let same: Vec<u32> = (0..500).collect();
+        let deque: std::collections::VecDeque<char> = "ab".chars().collect();
+        assert_eq!(values, same);
+        assert_eq!(&values[..3], [0, 1, 2], "{}", "wrong prefix");
+        assert_eq!(vec!["a", "b"], ["a", "b"]);
+        assert_eq!(deque, vec!['a', 'b']);
+        assert_eq!(Box::new([1, 2]), Box::new([1, 2]));
+        assert_ne!(values, vec![]);
+        debug_assert_eq!(values, same);
+        // the values were not moved by the assertions
+        drop((values, same, deque));
+    }
+}
+
+test_should_panic!(
+    panic_sequence_equality1,
+    assert_eq!(
+        (0..500).collect::<Vec<u32>>(),
+        (0..500)
+            .map(|v| if v == 250 { 0 } else { v })
+            .collect::<Vec<u32>>()
+    )
+);
+test_should_panic!(
+    panic_sequence_equality2,
+    assert_eq!(
+        vec![Some(1), None],
+        [Some(1), None, Some(3)],
+        "{}",
+        "too short"
+    )
+);
+test_should_panic!(
+    panic_sequence_equality3,
+    assert_eq!(&[1, 2, 3][..], &[1, 2][..])
+);
+test_should_panic!(
+    panic_sequence_equality4,
+    assert_eq!(vec![f64::NAN], vec![f64::NAN])
+);
+test_should_panic!(panic_sequence_equality5, assert_eq!("hello", "world"));
+
+test_all_assertification_styles! {
+    #[test]
+    fn string_equality() {
+        let rendered = String::from("fn main() {\n    println!(\"hello\");\n}\n");
+        let expected = "fn main() {\n    println!(\"hello\");\n}\n";
+        assert_eq!(rendered, expected);
+        assert_eq!(expected, rendered, "{}", "rendered differently");
+        assert_eq!(rendered.as_str(), std::borrow::Cow::Borrowed(expected));
+        assert_ne!(rendered, "fn main() {}\n");
+        debug_assert_eq!(rendered, expected);
+        // the values were not moved by the assertions
+        drop(rendered);
+    }
+}
+
+test_should_panic!(
+    panic_string_equality1,
+    assert_eq!(String::from("a\nb\nc\n"), "a\nB\nc\n")
+);
+test_should_panic!(
+    panic_string_equality2,
+    assert_eq!("line\r\nline \n", "line\nline\n", "{}", "whitespace")
+);
+test_should_panic!(
+    panic_string_equality3,
+    assert_eq!("a\tb\n\u{200b}", "a    b\n")
+);
+test_should_panic!(
+    panic_string_equality4,
+    assert_eq!("single line", "other line")
+);
+
+#[derive(Debug, PartialEq, Clone)]
+struct Tls {
+    cert_path: String,
+    ciphers: Vec<&'static str>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Mode {
+    Plain,
+    Tls(Tls),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Server {
+    port: u16,
+    mode: Mode,
+    labels: std::collections::BTreeMap<&'static str, &'static str>,
+}
+
+/// a server configuration with some nested values
+fn server() -> Server {
+    Server {
+        port: 8080,
+        mode: Mode::Tls(Tls {
+            cert_path: String::from("/etc/cert.pem"),
+            ciphers: vec!["aes", "chacha"],
+        }),
+        labels: vec![("env", "test"), ("key: value", "}")]
+            .into_iter()
+            .collect(),
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn struct_equality() {
+        let expected = server();
+        assert_eq!(server(), expected);
+        assert_eq!(&expected, &server(), "{}", "different server");
+        assert_eq!(Some(server()), Some(expected.clone()));
+        assert_ne!(server(), Server { mode: Mode::Plain, ..server() });
+        debug_assert_eq!(server(), expected);
+        // the values were not moved by the assertions
+        drop(expected);
+    }
+}
+
+test_should_panic!(
+    panic_struct_equality1,
+    assert_eq!(
+        server(),
+        Server {
+            port: 8081,
+            ..server()
+        }
+    )
+);
+test_should_panic!(
+    panic_struct_equality2,
+    assert_eq!(
+        server(),
+        Server {
+            mode: Mode::Plain,
+            ..server()
+        },
+        "{}",
+        "tls"
+    )
+);
+test_should_panic!(
+    panic_struct_equality3,
+    assert_eq!(
+        Mode::Plain,
+        Mode::Tls(match server().mode {
+            Mode::Tls(tls) => tls,
+            Mode::Plain => unreachable!(),
+        })
+    )
+);
+test_should_panic!(panic_struct_equality4, assert_eq!(Some(server()), None));
+
+/// a key that implements Hash but not Ord
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+/// a map with the numbers from 1 to 5 as keys and their squares as values
+fn squares() -> std::collections::HashMap<i32, i32> {
+    (1..=5).map(|i| (i, i * i)).collect()
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn map_equality() {
+        let expected = squares();
+        let colors: std::collections::HashSet<Color> = vec![Color::Red, Color::Blue].into_iter().collect();
+        let ordered: std::collections::BTreeSet<&str> = vec!["a", "b"].into_iter().collect();
+        assert_eq!(squares(), expected);
+        assert_eq!(&expected, &squares(), "{}", "different squares");
+        assert_eq!(colors, vec![Color::Blue, Color::Red].into_iter().collect());
+        assert_eq!(ordered, vec!["b", "a"].into_iter().collect());
+        assert_ne!(squares(), std::collections::HashMap::new());
+        debug_assert_eq!(squares(), expected);
+        // the values were not moved by the assertions
+        drop(expected);
+    }
+}
+
+test_should_panic!(
+    panic_map_equality1,
+    assert_eq!(squares(), (0..5).map(|i| (i, i * i)).collect())
+);
+test_should_panic!(
+    panic_map_equality2,
+    assert_eq!(
+        squares(),
+        (1..=5).map(|i| (i, i * i + i % 2)).collect(),
+        "{}",
+        "odd squares"
+    )
+);
+test_should_panic!(
+    panic_map_equality3,
+    assert_eq!(
+        vec![Color::Red, Color::Green]
+            .into_iter()
+            .collect::<std::collections::HashSet<_>>(),
+        vec![Color::Red, Color::Blue].into_iter().collect()
+    )
+);
+test_should_panic!(
+    panic_map_equality4,
+    assert_eq!(
+        std::collections::BTreeMap::from([("a", 1), ("b", 2)]),
+        std::collections::BTreeMap::from([("a", 1)])
+    )
+);
+
+test_all_assertification_styles! {
+    #[test]
+    fn absolute_differences() {
+        let (a, b): (f64, f64) = (0.1 + 0.2, 0.3);
+        let tolerance = 1e-9;
+        assert!((a - b).abs() < 1e-9);
+        assert!((a - b).abs() <= tolerance, "{}", "close enough");
+        assert!(tolerance > (a - b).abs());
+        assert!((1.0f32 - 2.0).abs() >= 1.0);
+        assert!((3i32 - 5).abs() == 2);
+        assert!((a - b).abs() < tolerance && (b - a).abs() < tolerance);
+        debug_assert!((a - b).abs() < tolerance);
+    }
+}
+
+test_should_panic!(
+    panic_absolute_difference1,
+    assert!((1.0 - 1.1f64).abs() < 1e-9)
+);
+test_should_panic!(
+    panic_absolute_difference2,
+    assert!((0.5f32 - 0.25).abs() <= 0.125, "{}", "too far apart")
+);
+test_should_panic!(
+    panic_absolute_difference3,
+    assert!(1e-9 > (1.0 - f64::NAN).abs())
+);
+test_should_panic!(panic_absolute_difference4, assert!((2 - 2i32).abs() >= 1));
+
+test_all_assertification_styles! {
+    #[test]
+    fn collection_lengths() {
+        let values = vec![I(1), I(2), I(3)];
+        let empty: Vec<I> = Vec::new();
+        let map: std::collections::HashMap<i32, &str> = vec![(1, "one")].into_iter().collect();
+        assert!(empty.is_empty());
+        assert!(!values.is_empty(), "{}", "no values");
+        assert!(String::new().is_empty() && !map.is_empty());
+        assert_eq!(values.len(), 3);
+        assert_eq!(3, values.len(), "{}", "wrong length");
+        assert_ne!(map.len(), 0);
+        assert!(values.len() > empty.len());
+        assert!(2 <= values.len());
+        assert!(values[1..].len() == 2);
+        debug_assert_eq!(empty.len(), 0);
+        debug_assert!(!map.is_empty());
+        // the values were not moved by the assertions
+        drop((values, empty, map));
+    }
+}
+
+test_should_panic!(panic_collection_length1, assert!(vec![1, 2].is_empty()));
+test_should_panic!(
+    panic_collection_length2,
+    assert!(!Vec::<I>::new().is_empty(), "{}", "no values")
+);
+test_should_panic!(panic_collection_length3, assert_eq!(vec![I(1)].len(), 2));
+test_should_panic!(
+    panic_collection_length4,
+    assert_ne!(0, Vec::<I>::new().len())
+);
+test_should_panic!(panic_collection_length5, assert!(vec![1; 1000].len() < 10));
+test_should_panic!(panic_collection_length6, assert!("hello".len() == 3));
+
+test_all_assertification_styles! {
+    @args(panics)
+    #[test]
+    fn if_panic_idioms() {
+        let (x, y) = (I(1), I(2));
+        let values = vec![1, 2, 3];
+        let empty: Vec<i32> = Vec::new();
+        if x == y {
+            panic!("mismatch");
+        }
+        if x != I(1) {
+            panic!("mismatch: {} vs {}", x.0, 1)
+        }
+        if !(x < y) {
+            panic!()
+        }
+        if values.len() > 3 || empty.len() > 1 {
+            std::panic!("{} values", values.len());
+        }
+        if values.is_empty() {
+            core::panic!("no values");
+        }
+        if !empty.is_empty() {
+            panic!("{:?}", empty)
+        }
+        if x.0 + 1 < 0 && y.0 > 0 {
+            panic!("negative");
+        }
+        // the idiom inside of nested code is replaced, too
+        for value in values.iter() {
+            if *value == 0 {
+                panic!("zero at {}", value);
+            }
+        }
+        // anything other than a single panic is left alone
+        if x == y {
+            println!("equal");
+            panic!("mismatch");
+        }
+        if x == y {
+            panic!("mismatch");
+        } else {
+            println!("different");
+        }
+    }
+}
+
+test_should_panic!(
+    @args(panics) panic_if_panic1,
+    if I(1) != I(2) {
+        panic!("mismatch")
+    }
+);
+test_should_panic!(
+    @args(panics) panic_if_panic2,
+    if 1 + 1 == 2 {
+        panic!("{} is two", 1 + 1);
+    }
+);
+test_should_panic!(
+    @args(panics) panic_if_panic3,
+    if vec![1, 2].len() >= 2 || false {
+        panic!()
+    }
+);
+test_should_panic!(
+    @args(panics) panic_if_panic4,
+    if !vec![1].is_empty() {
+        std::panic!("not empty")
+    }
+);
+
+fn is_even(value: i32) -> bool {
+    value % 2 == 0
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn bool_literal_comparisons() {
+        let value: Option<i32> = Some(4);
+        let values = vec![1, 2];
+        assert_eq!(matches!(value, Some(4)), true);
+        assert_eq!(true, matches!(value, Some(x) if x > 3), "{}", "too small");
+        assert_eq!(is_even(3), false);
+        assert_ne!(is_even(4), false);
+        assert_ne!(true, is_even(5));
+        assert_eq!(value.is_some(), true);
+        assert_eq!(values.is_empty(), false);
+        assert_eq!(values.contains(&2), true);
+        assert_eq!(value == Some(5), false);
+        assert_eq!(is_even(2) && is_even(4), true);
+        debug_assert_eq!(is_even(1), false);
+        assert_eq!(!values.is_empty(), true);
+        assert_ne!(false, value.is_none() || values.contains(&1));
+        // types that can be compared with a bool keep the meaning of the comparison
+        assert_eq!(Flag(true), true);
+        assert_ne!(Flag(false), true);
+        assert_eq!(Flag::new(values.is_empty()), false);
+    }
+}
+
+/// a type that can be compared with a bool, but is not one
+#[derive(Debug)]
+struct Flag(bool);
+
+impl Flag {
+    fn new(value: bool) -> Self {
+        Self(value)
+    }
+}
+
+impl PartialEq<bool> for Flag {
+    fn eq(&self, other: &bool) -> bool {
+        self.0 == *other
+    }
+}
+
+test_should_panic!(
+    panic_bool_literal1,
+    assert_eq!(matches!(Some(1), None), true)
+);
+test_should_panic!(panic_bool_literal2, assert_eq!(is_even(2), false));
+test_should_panic!(
+    panic_bool_literal3,
+    assert_ne!(true, Option::<i32>::None.is_none(), "{}", "is none")
+);
+test_should_panic!(panic_bool_literal4, assert_eq!(vec![I(1)].is_empty(), true));
+test_should_panic!(panic_bool_literal5, assert_ne!(Flag(true), true));
+
+#[derive(Debug)]
+enum Shape {
+    Circle { radius: i32 },
+    Square(i32),
+    Point,
+}
+
+test_all_assertification_styles! {
+    @args(panics)
+    #[test]
+    fn diverging_pattern_idioms() {
+        let parsed: Result<i32, String> = Ok(2);
+        let shape = Shape::Square(3);
+        let value = match parsed {
+            Ok(value) => value,
+            Err(error) => panic!("unexpected {:?}", error),
+        };
+        let side = match &shape {
+            Shape::Square(side) => *side * value,
+            Shape::Circle { .. } => unreachable!("not a circle"),
+            Shape::Point => panic!(),
+        };
+        let doubled = match Some(side) {
+            None => panic!("no side"),
+            Some(side) => {
+                assert!(side > 0);
+                side * 2
+            }
+        };
+        let circle = Shape::Circle { radius: doubled };
+        let radius = if let Shape::Circle { radius } = circle {
+            radius + 1
+        } else {
+            unreachable!()
+        };
+        let Some(squared) = Some(radius * radius) else {
+            panic!("{} has no square", radius);
+        };
+        if let Shape::Square(x) | Shape::Circle { radius: x } = Shape::Square(squared) {
+            assert_eq!(x, 169);
+        } else {
+            panic!()
+        }
+        // the value of `x` in the outer scope is not shadowed by the bindings of the pattern
+        let x = 1;
+        let y = match Some(2) {
+            Some(x) => x,
+            None => panic!(),
+        };
+        assert_eq!(x + y, 3);
+        // matches in which another arm might match the same value are left alone
+        let small = match 3 {
+            0 => panic!("zero"),
+            n => n,
+        };
+        assert_eq!(small, 3);
+    }
+}
+
+struct Parser {
+    state: Option<String>,
+}
+
+test_all_assertification_styles! {
+    @args(panics)
+    #[test]
+    fn diverging_pattern_idioms_do_not_move_the_value() {
+        let parser = &Parser {
+            state: Some(String::from("ready")),
+        };
+        let length = match parser.state {
+            Some(ref state) => state.len(),
+            None => panic!(),
+        };
+        let option = Some(String::from("value"));
+        match option {
+            Some(_) => {}
+            None => panic!("no value"),
+        }
+        if let Some(ref text) = option {
+            assert_eq!(text.len(), length);
+        } else {
+            unreachable!()
+        }
+        let Some(ref text) = option else {
+            panic!("{}", "no text");
+        };
+        let Some(mut copy) = option.clone() else {
+            panic!();
+        };
+        copy.push_str(text);
+        assert_eq!(copy, "valuevalue");
+        // the value is still available after the idioms
+        assert_eq!(option, Some(String::from("value")));
+        // the arguments of the diverging macro may refer to a variable called `value`
+        let value = 1;
+        let Some(number) = Some(value + 1) else {
+            panic!("{}", value);
+        };
+        assert_eq!(number, 2);
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn negative_patterns() {
+        let shape = Shape::Square(3);
+        let name: Option<String> = Some(String::from("square"));
+        assert!(!matches!(shape, Shape::Point));
+        assert!(!matches!(shape, Shape::Circle { .. } | Shape::Point), "{}", "round");
+        assert!(!matches!(shape, Shape::Square(side) if side > 3));
+        assert!(!(matches!(&name, Some(n) if n.is_empty())));
+        assert!(!std::matches!(name.as_deref(), Some("circle")));
+        assert!(!matches!(name, None) && !matches!(shape, Shape::Square(0)));
+        debug_assert!(!matches!(shape, Shape::Circle { radius } if radius > 0));
+        assert_eq!(matches!(shape, Shape::Point), false);
+        // the values were not moved by the assertions
+        drop((shape, name));
+    }
+}
+
+test_should_panic!(
+    panic_negative_pattern1,
+    assert!(!matches!(Some(1), Some(_)))
+);
+test_should_panic!(
+    panic_negative_pattern2,
+    assert!(
+        !matches!(Shape::Square(4), Shape::Square(side) if side > 3),
+        "{}",
+        "too big"
+    )
+);
+test_should_panic!(
+    panic_negative_pattern3,
+    assert!(!matches!(String::from("x"), s if s.len() == 1))
+);
+test_should_panic!(
+    panic_negative_pattern4,
+    assert!(true && !core::matches!(Shape::Point, Shape::Point | Shape::Square(_)))
+);
+
+test_should_panic!(
+    @args(panics) panic_diverging_pattern1,
+    match Result::<i32, String>::Err(String::from("bad")) {
+        Ok(value) => value,
+        Err(error) => panic!("unexpected {:?}", error),
+    }
+);
+test_should_panic!(
+    @args(panics) panic_diverging_pattern2,
+    match Shape::Point {
+        Shape::Circle { radius } => radius,
+        _ => unreachable!("{}", "not a circle"),
+    }
+);
+test_should_panic!(
+    @args(panics) panic_diverging_pattern3,
+    if let Some(value) = Option::<i32>::None {
+        value
+    } else {
+        unreachable!()
+    }
+);
+test_should_panic!(
+    @args(panics) panic_diverging_pattern4,
+    match Option::<Shape>::None {
+        None => panic!("nothing"),
+        Some(shape) => shape,
+    }
+);
+// the diverging arms may move the values they bind, just like before
+test_should_panic!(
+    @args(panics) panic_diverging_pattern5,
+    match Result::<i32, String>::Err(String::from("bad")) {
+        Ok(value) => value,
+        Err(error) => panic!("{} bytes", error.into_bytes().len()),
+    }
+);
+
+test_all_assertification_styles! {
+    #[test]
+    fn iterator_predicates() {
+        let values = vec![1, 2, 3, 4];
+        let names = vec![String::from("foo"), String::from("bar")];
+        assert!(values.iter().all(|v| *v > 0));
+        assert!(values.iter().any(|&v| v % 3 == 0), "{}", "no multiple of three");
+        assert!(values.iter().all(|v| *v > 0) && values.iter().any(|v| *v > 3));
+        assert!(names.iter().all(|name| name.len() == 3));
+        assert!(names.clone().into_iter().any(|name| name == "bar"));
+        assert!((0..10).map(I).all(|i| i.0 < 10));
+        let mut rest = values.iter();
+        rest.next();
+        assert!(rest.all(|v| *v > 1));
+        debug_assert!(values.iter().any(|v| *v < 2));
+        // the values were not moved by the assertions
+        drop((values, names));
+    }
+}
+
+test_should_panic!(
+    panic_iterator_predicate1,
+    assert!(vec![1, 2, -3, 4].iter().all(|v| *v >= 0))
+);
+test_should_panic!(
+    panic_iterator_predicate2,
+    assert!(vec![1, 2, 3].into_iter().any(|v| v > 3), "{}", "too small")
+);
+test_should_panic!(
+    panic_iterator_predicate3,
+    assert!(vec![I(1), I(2)].into_iter().all(|i| i.0 == 1))
+);
+test_should_panic!(
+    panic_iterator_predicate4,
+    assert!(vec![String::from("foo")].into_iter().any(|s| s.is_empty()))
+);
+test_should_panic!(
+    panic_iterator_predicate5,
+    assert!(Vec::<i32>::new().iter().any(|v| *v < 0))
+);
+
+/// a type that is not an iterator, but has `all` and `any` methods like the parallel
+/// iterators of rayon or bit flags
+#[derive(Clone)]
+struct Flags(Vec<bool>);
+
+impl Flags {
+    fn all(&self, predicate: impl Fn(&bool) -> bool) -> bool {
+        self.0.iter().all(predicate)
+    }
+
+    fn any(self, predicate: impl Fn(bool) -> bool + Sync + Send) -> bool {
+        self.0.into_iter().any(predicate)
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn predicates_of_other_types_than_iterators() {
+        let flags = Flags(vec![true, true]);
+        assert!(flags.all(|flag| *flag));
+        assert!(flags.clone().any(|flag| flag), "{}", "no flag");
+        assert!(Flags(vec![false]).all(|flag| !flag) && flags.clone().any(|flag| flag));
+        assert!(flags.any(|flag| flag));
+    }
+}
+
+test_should_panic!(
+    panic_non_iterator_predicate1,
+    assert!(Flags(vec![true, false]).all(|flag| *flag))
+);
+test_should_panic!(
+    panic_non_iterator_predicate2,
+    assert!(Flags(vec![false]).any(|flag| flag), "{}", "no flag")
+);
+
+test_all_assertification_styles! {
+    #[test]
+    fn debug_assert_pass() {
+        debug_assert!(1 == 1);
+        debug_assert_eq!(1, 1);
+        debug_assert_ne!(1, 2);
+
+        debug_assert!(1 == 1, "{}", "math broke");
+        debug_assert_eq!(1, 1, "{}", "math broke");
+        debug_assert_ne!(1, 2, "{}", "math broke");
+
+        debug_assert!(matches!(Result::<i32, i32>::Ok(10), Ok(10)));
+        debug_assert!(matches!(Result::<i32, i32>::Ok(10), Ok(x) if x > 5), "{}", "rust broke");
+    }
+}
+
+// debug assertions must only panic if debug assertions are enabled
+#[cfg(debug_assertions)]
+mod failing_debug_assertions {
+    test_should_panic!(panic_debug_assert1, debug_assert!(1 == 2));
+    test_should_panic!(
+        panic_debug_assert2,
+        debug_assert!(1 == 2, "{}", "math broke")
+    );
+    test_should_panic!(panic_debug_assert3, debug_assert_eq!(1, 2));
+    test_should_panic!(
+        panic_debug_assert4,
+        debug_assert_ne!(1, 1, "{}", "math broke")
+    );
+    test_should_panic!(
+        panic_debug_assert5,
+        debug_assert!(matches!(Result::<i32, i32>::Err(10), Ok(_)))
+    );
+    test_should_panic!(
+        panic_debug_assert6,
+        debug_assert!(matches!(Result::<i32, i32>::Ok(10), Ok(x) if x > 20))
+    );
+}
+
+#[cfg(not(debug_assertions))]
+test_all_assertification_styles! {
+    #[test]
+    fn debug_assertions_are_not_checked_without_debug_assertions() {
+        debug_assert!(1 == 2);
+        debug_assert_eq!(1, 2);
+        debug_assert_ne!(1, 1);
+        debug_assert!(matches!(Result::<i32, i32>::Err(10), Ok(_)));
+    }
+}
+
+test_all_assertification_styles! {
+    #[test]
+    fn qualified_assert_pass() {
+        std::assert!(1 == 1);
+        ::std::assert_eq!(1, 1);
+        core::assert_ne!(1, 2);
+        ::core::assert!(1 == 1, "{}", "math broke");
+        core::debug_assert_eq!(1, 1);
+        std::prelude::v1::assert!(1 == 1);
+        ::core::prelude::v1::assert_eq!(1, 1);
+
+        assert!(std::matches!(Result::<i32, i32>::Ok(10), Ok(10)));
+        core::assert!(core::matches!(Result::<i32, i32>::Ok(10), Ok(x) if x > 5));
+        ::core::assert!(::core::matches!(Result::<i32, i32>::Ok(10), Ok(10)), "{}", "rust broke");
+    }
+}
+
+test_should_panic!(panic_qualified_assert1, core::assert!(1 == 2));
+test_should_panic!(panic_qualified_assert2, ::core::assert_eq!(1, 2));
+test_should_panic!(panic_qualified_assert3, std::prelude::v1::assert_ne!(1, 1));
+test_should_panic!(
+    panic_qualified_assert4,
+    assert!(core::matches!(Result::<i32, i32>::Err(10), Ok(_)))
+);
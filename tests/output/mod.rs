@@ -0,0 +1,662 @@
+//! Test the output of failed assertions. The failing assertions run in a child process, which
+//! runs nothing but the test that called [printed_by], so that we can capture what they print.
+
+#![allow(clippy::useless_vec)]
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::process::Command;
+
+use assert2ify::assert2ify;
+
+/// the environment variable that tells the test in the child process to run the failing assertions
+const CHILD_PROCESS_VARIABLE: &str = "ASSERT2IFY_OUTPUT_TEST";
+
+/// Runs the given failing assertions in a child process, which runs only the test with the given
+/// name, i.e. the test that calls this function.
+/// # Returns
+/// The lines that the assertions printed, without colors, for each failure. The location of each
+/// failure (the line `Assertion failed at file:line:column:`) is left out.
+fn printed_by(test_name: &str, assertions: fn()) -> Vec<Vec<String>> {
+    if env::var_os(CHILD_PROCESS_VARIABLE).is_some() {
+        assertions();
+        return Vec::new();
+    }
+    let output = Command::new(env::current_exe().unwrap())
+        .args([test_name, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD_PROCESS_VARIABLE, "1")
+        .env("ASSERT2", "no-color")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    stderr
+        .split("Assertion failed at ")
+        .skip(1)
+        .map(|failure| {
+            // each failure ends with an empty line, the last one is followed by the panic message
+            failure
+                .lines()
+                .skip(1)
+                .take_while(|line| !line.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .collect()
+}
+
+/// Generates a test for each function with failing assertions, which checks the lines that the
+/// assertions print for each failure, see [printed_by]. The syntax is
+/// `test_name: assertions => [[lines of the first failure], [lines of the second failure], ...]`
+macro_rules! test_output {
+    ($($test_name:ident: $assertions:ident => [$([$($line:expr),* $(,)?]),* $(,)?]),* $(,)?) => {
+        $(
+            #[test]
+            fn $test_name() {
+                let printed = printed_by(::std::concat!("output::", ::std::stringify!($test_name)), $assertions);
+                let expected: &[&[&str]] = &[$(&[$($line),*]),*];
+                assert_eq!(printed, expected);
+            }
+        )*
+    };
+}
+
+fn something() -> Result<i32, String> {
+    Ok(1337)
+}
+
+#[assert2ify(check)]
+fn pattern_checks() {
+    assert!(matches!(something(), Err(_)), "something {}", "is wrong");
+}
+
+fn error() -> Result<i32, String> {
+    Err("bla".to_string())
+}
+
+#[assert2ify(check)]
+fn guard_checks() {
+    assert!(
+        matches!(error(), Err(s) if s.contains("foo")),
+        "something {}",
+        "is wrong"
+    );
+}
+
+#[assert2ify(check)]
+fn conjunction_checks() {
+    let v: Vec<i32> = Vec::new();
+    // this would panic with an out of bounds access if the second conjunct was evaluated
+    assert!(!v.is_empty() && v[0] == 1, "{}", "empty vector");
+    assert!(matches!(v.first(), Some(_)) && v[0] == 1);
+}
+
+#[assert2ify(check)]
+fn variant_checks() {
+    let result = error();
+    assert!(result.is_ok(), "{}", "result is not ok");
+    assert!(something().is_err());
+    assert!(result.unwrap_err() == "bla");
+}
+
+#[assert2ify(check)]
+fn method_call_checks() {
+    let log = "[info] connected to the server after 3 retries\n[info] sending data";
+    assert!(log.contains("connection"), "{}", "not connected");
+    assert!(log.ends_with("sending data\n"));
+    assert!(vec![1, 2, 3].contains(&4));
+}
+
+/// the closures are called as often as without the translation, which the checks of the
+/// number of calls would print otherwise
+#[assert2ify(check)]
+fn iterator_checks() {
+    let values = vec![1, -2, 3, -4];
+    let mut calls = 0;
+    assert!(values.iter().all(|v| {
+        calls += 1;
+        *v > 0
+    }));
+    assert_eq!(calls, 2);
+    assert!(
+        values.iter().any(|v| {
+            calls += 1;
+            *v > 5
+        }),
+        "{}",
+        "no large value"
+    );
+    assert_eq!(calls, 6);
+    assert!(values.iter().any(|v| {
+        calls += 1;
+        *v == 1
+    }));
+    assert_eq!(calls, 7);
+}
+
+/// a type with an `all` method, which is not an iterator
+struct Switches(Vec<bool>);
+
+impl Switches {
+    fn all(&self, predicate: impl Fn(&bool) -> bool) -> bool {
+        self.0.iter().all(predicate)
+    }
+}
+
+#[assert2ify(check)]
+fn non_iterator_checks() {
+    assert!(Switches(vec![true, false]).all(|on| *on));
+}
+
+#[assert2ify(check)]
+fn collection_checks() {
+    let values: Vec<i32> = (0..5).collect();
+    assert!(values.is_empty(), "{}", "too many values");
+    assert_eq!(values.len(), 3);
+    assert!(2 > values.len());
+    assert!(values[..3].len() == 3);
+}
+
+#[assert2ify(check)]
+fn sequence_checks() {
+    let values: Vec<u32> = (0..500).collect();
+    let mut other = values.clone();
+    other[250] = 0;
+    assert_eq!(values, other, "{}", "one different value");
+    other.truncate(2);
+    assert_eq!(&values[..3], other.as_slice());
+    assert_eq!(values[0], 1);
+}
+
+#[assert2ify(check)]
+fn string_checks() {
+    let expected: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+    let rendered = expected
+        .replace("line 3\n", "line 3 \r\n")
+        .replace("line 15\n", "line\t15\n\u{feff}line 15.5\n")
+        .trim_end()
+        .to_string();
+    assert_eq!(rendered, expected, "{}", "rendered differently");
+    assert_eq!("single line", "other line");
+}
+
+#[derive(Debug, PartialEq)]
+struct Tls {
+    cert_path: String,
+    protocols: Vec<&'static str>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Server {
+    host: &'static str,
+    port: u16,
+    tls: Option<Tls>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Config {
+    server: Server,
+    workers: BTreeMap<&'static str, (u8, bool)>,
+}
+
+/// a configuration with the given certificate path, protocols and workers
+fn config(
+    cert_path: &str,
+    protocols: Vec<&'static str>,
+    workers: &[(&'static str, (u8, bool))],
+) -> Config {
+    Config {
+        server: Server {
+            host: "localhost",
+            port: 443,
+            tls: Some(Tls {
+                cert_path: cert_path.to_string(),
+                protocols,
+            }),
+        },
+        workers: workers.iter().cloned().collect(),
+    }
+}
+
+#[assert2ify(check)]
+fn small_value_checks() {
+    assert_eq!(Some(1), Some(2));
+    assert_eq!((1, "a"), (1, "b"));
+}
+
+#[assert2ify(check)]
+fn struct_checks() {
+    let expected = config(
+        "/etc/cert.pem",
+        vec!["tls1.2", "tls1.3"],
+        &[("a", (1, true)), ("b", (2, false))],
+    );
+    let actual = config(
+        "/etc/key.pem",
+        vec!["tls1.2"],
+        &[("a", (1, true)), ("b", (3, false)), ("c", (1, true))],
+    );
+    assert_eq!(actual, expected, "{}", "wrong config");
+    let without_tls = Config {
+        server: Server {
+            tls: None,
+            ..actual.server
+        },
+        ..actual
+    };
+    assert_eq!(without_tls, expected);
+}
+
+#[assert2ify(check)]
+fn map_checks() {
+    let expected: HashMap<u32, String> = (0..100).map(|i| (i, i.to_string())).collect();
+    let mut actual = expected.clone();
+    actual.remove(&42);
+    actual.insert(7, String::from("seven"));
+    actual.insert(100, String::from("100"));
+    assert_eq!(actual, expected, "{}", "wrong numbers");
+    let actual: HashSet<&str> = vec!["tls1.2", "tls1.3"].into_iter().collect();
+    assert_eq!(actual, vec!["tls1.3"].into_iter().collect());
+}
+
+#[assert2ify(check)]
+fn byte_checks() {
+    let expected: Vec<u8> = (0..=255).collect();
+    let mut encoded = expected.clone();
+    encoded[0x21] = b'?';
+    encoded[0x9a] = 0;
+    encoded.truncate(250);
+    assert_eq!(encoded, expected, "{}", "wrong encoding");
+    assert_eq!(&b"\x00\x01ab"[..], b"\x00\x01ac");
+}
+
+fn approximate_checks() {
+    assert2ify::check_approx_eq!(0.1 + 0.2, 0.30001, abs = 1e-9, "{}", "not close");
+    assert2ify::check_approx_eq!(f32::NAN, 1.0);
+}
+
+#[assert2ify(check)]
+fn absolute_difference_checks() {
+    let (measured, expected): (f64, f64) = (0.1 + 0.2, 0.30001);
+    assert!((measured - expected).abs() < 1e-9, "{}", "not close");
+    assert!(1e-9 >= (measured - expected).abs());
+}
+
+#[assert2ify(check, panics)]
+fn if_panic_checks() {
+    let (left, right) = (1, 2);
+    if left != right {
+        panic!("mismatch: {} vs {}", left, right);
+    }
+    if vec![1, 2, 3].len() > 2 {
+        panic!("too many values")
+    }
+}
+
+#[assert2ify(check, panics)]
+fn diverging_pattern_checks() {
+    let _port = match error() {
+        Ok(port) => port,
+        Err(error) => panic!("unexpected {:?}", error),
+    };
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+enum Shape {
+    Point,
+    Square(u32),
+}
+
+#[assert2ify(check)]
+fn negative_pattern_checks() {
+    let shape = Shape::Square(4);
+    assert!(
+        !matches!(shape, Shape::Point | Shape::Square(_)),
+        "{}",
+        "not a line"
+    );
+    assert!(!matches!(shape, Shape::Square(side) if side > 3));
+}
+
+test_output! {
+    pattern_checks_print_the_value_that_does_not_match: pattern_checks => [
+        [
+            "  check!( let Err(_) = something() )",
+            "with expansion:",
+            "  Ok(1337)",
+            "with message:",
+            "  something is wrong",
+        ],
+    ],
+    guard_checks_print_the_bindings_of_the_pattern: guard_checks => [
+        [
+            "  check!( s.contains(\"foo\") )",
+            "with:",
+            "  s = \"bla\"",
+            "with expansion:",
+            "  false",
+            "with message:",
+            "  something is wrong",
+        ],
+    ],
+    conjunction_checks_print_the_first_failed_conjunct: conjunction_checks => [
+        [
+            "  check!( ! v.is_empty() )",
+            "with:",
+            "  v = []",
+            "with expansion:",
+            "  false",
+            "with message:",
+            "  empty vector",
+        ],
+        [
+            "  check!( let Some(_) = v.first() )",
+            "with expansion:",
+            "  None",
+        ],
+    ],
+    variant_checks_print_the_value: variant_checks => [
+        [
+            "  check!( let Ok(_) = result )",
+            "with expansion:",
+            "  Err(\"bla\")",
+            "with message:",
+            "  result is not ok",
+        ],
+        [
+            "  check!( let Err(_) = something() )",
+            "with expansion:",
+            "  Ok(1337)",
+        ],
+    ],
+    method_call_checks_print_the_receiver_and_the_arguments: method_call_checks => [
+        [
+            "  check!( log.contains(\"connection\") )",
+            "with expansion:",
+            "  \"[info] connected to the server after 3 retries\\n[info] sending data\".contains(\"connection\")",
+            "closest partial match at byte 7:",
+            "  [info] connected to the server after 3...",
+            "         ^^^^^^^",
+            "with message:",
+            "  not connected",
+        ],
+        [
+            "  check!( log.ends_with(\"sending data\\n\") )",
+            "with expansion:",
+            "  \"[info] connected to the server after 3 retries\\n[info] sending data\".ends_with(\"sending data\\n\")",
+        ],
+        [
+            "  check!( vec! [1, 2, 3].contains(& 4) )",
+            "with expansion:",
+            "  [1, 2, 3].contains(4)",
+        ],
+    ],
+    iterator_checks_print_the_offending_elements_and_call_the_closures_as_often_as_before: iterator_checks => [
+        [
+            "  check!( values.iter().all(| v | { calls += 1; * v > 0 }) )",
+            "with expansion:",
+            "  the element at index 1 does not satisfy the predicate:",
+            "  -2",
+        ],
+        [
+            "  check!( values.iter().any(| v | { calls += 1; * v > 5 }) )",
+            "with expansion:",
+            "  none of the 4 elements satisfies the predicate:",
+            "  [1, -2, 3, -4]",
+            "with message:",
+            "  no large value",
+        ],
+    ],
+    non_iterator_checks_print_the_expression: non_iterator_checks => [
+        [
+            "  check!( Switches(vec! [true, false]).all(| on | * on) )",
+            "with expansion:",
+            "  false",
+        ],
+    ],
+    collection_checks_print_the_collection: collection_checks => [
+        [
+            "  check!( values.is_empty() )",
+            "with:",
+            "  values = [0, 1, 2, 3, 4]",
+            "with expansion:",
+            "  false",
+            "with message:",
+            "  too many values",
+        ],
+        [
+            "  check!( values.len() == 3 )",
+            "with:",
+            "  values = [0, 1, 2, 3, 4]",
+            "with expansion:",
+            "  5 == 3",
+        ],
+        [
+            "  check!( 2 > values.len() )",
+            "with:",
+            "  values = [0, 1, 2, 3, 4]",
+            "with expansion:",
+            "  2 > 5",
+        ],
+    ],
+    sequence_checks_print_a_sequence_diff: sequence_checks => [
+        [
+            "  check!( values == other )",
+            "with sequence diff:",
+            "  first difference at index 250",
+            "  ...",
+            "  [247] 247 == 247",
+            "  [248] 248 == 248",
+            "  [249] 249 == 249",
+            "  [250] 250 != 0",
+            "  [251] 251 == 251",
+            "  [252] 252 == 252",
+            "  [253] 253 == 253",
+            "  ...",
+            "with message:",
+            "  one different value",
+        ],
+        [
+            "  check!( & values [.. 3] == other.as_slice() )",
+            "with sequence diff:",
+            "  first difference at index 2",
+            "  lengths differ: left has 3 elements, right has 2 elements",
+            "  [0] 0 == 0",
+            "  [1] 1 == 1",
+            "  [2] 2 != (no element)",
+        ],
+        [
+            "  check!( values [0] == 1 )",
+            "with expansion:",
+            "  0 == 1",
+        ],
+    ],
+    string_checks_print_a_line_diff: string_checks => [
+        [
+            "  check!( rendered == expected )",
+            "with diff:",
+            "  @@ -1,6 +1,6 @@",
+            "   line 1",
+            "   line 2",
+            "  - line 3·␍",
+            "  + line 3",
+            "   line 4",
+            "   line 5",
+            "   line 6",
+            "  @@ -12,10 +12,9 @@",
+            "   line 12",
+            "   line 13",
+            "   line 14",
+            "  - line→15",
+            "  - \\u{feff}line 15.5",
+            "  + line 15",
+            "   line 16",
+            "   line 17",
+            "   line 18",
+            "   line 19",
+            "  - line 20 (no newline at end)",
+            "  + line 20",
+            "with message:",
+            "  rendered differently",
+        ],
+        [
+            "  check!( \"single line\" == \"other line\" )",
+            "with expansion:",
+            "  \"single line\" == \"other line\"",
+        ],
+    ],
+    small_value_checks_print_the_values_in_a_single_line: small_value_checks => [
+        [
+            "  check!( Some(1) == Some(2) )",
+            "with expansion:",
+            "  Some(1) == Some(2)",
+        ],
+        [
+            "  check!( (1, \"a\") == (1, \"b\") )",
+            "with expansion:",
+            "  (1, \"a\") == (1, \"b\")",
+        ],
+    ],
+    struct_checks_print_a_structural_diff: struct_checks => [
+        [
+            "  check!( actual == expected )",
+            "with structural diff:",
+            "  server.tls.0.cert_path: \"/etc/key.pem\" != \"/etc/cert.pem\"",
+            "  server.tls.0.protocols[1]: (missing) != \"tls1.3\"",
+            "  workers[\"b\"].0: 3 != 2",
+            "  workers[\"c\"]: (1, true) != (missing)",
+            "with message:",
+            "  wrong config",
+        ],
+        [
+            "  check!( without_tls == expected )",
+            "with structural diff:",
+            "  server.tls: None != Some(Tls { cert_path: \"/etc/cert.pem\", protocols: [\"tls1.2\", \"tls1.3\"] })",
+            "  workers[\"b\"].0: 3 != 2",
+            "  workers[\"c\"]: (1, true) != (missing)",
+        ],
+    ],
+    map_checks_print_a_map_diff: map_checks => [
+        [
+            "  check!( actual == expected )",
+            "with map diff:",
+            "  only in left:",
+            "    100: \"100\"",
+            "  only in right:",
+            "    42: \"42\"",
+            "  different values:",
+            "    7: \"seven\" != \"7\"",
+            "with message:",
+            "  wrong numbers",
+        ],
+        [
+            "  check!( actual == vec! [\"tls1.3\"].into_iter().collect() )",
+            "with map diff:",
+            "  only in left:",
+            "    \"tls1.2\"",
+        ],
+    ],
+    byte_checks_print_a_hex_diff: byte_checks => [
+        [
+            "  check!( encoded == expected )",
+            "with hex diff:",
+            "  first difference at offset 0x21",
+            "  lengths differ: left has 250 bytes, right has 256 bytes",
+            "  -00000020  20 3f 22 23 24 25 26 27  28 29 2a 2b 2c 2d 2e 2f  | ?\"#$%&'()*+,-./|",
+            "  +00000020  20 21 22 23 24 25 26 27  28 29 2a 2b 2c 2d 2e 2f  | !\"#$%&'()*+,-./|",
+            "  ...",
+            "  -00000090  90 91 92 93 94 95 96 97  98 99 00 9b 9c 9d 9e 9f  |................|",
+            "  +00000090  90 91 92 93 94 95 96 97  98 99 9a 9b 9c 9d 9e 9f  |................|",
+            "  ...",
+            "  -000000f0  f0 f1 f2 f3 f4 f5 f6 f7  f8 f9                    |..........|",
+            "  +000000f0  f0 f1 f2 f3 f4 f5 f6 f7  f8 f9 fa fb fc fd fe ff  |................|",
+            "with message:",
+            "  wrong encoding",
+        ],
+        [
+            "  check!( & b\"\\x00\\x01ab\" [..] == b\"\\x00\\x01ac\" )",
+            "with hex diff:",
+            "  first difference at offset 0x3",
+            "  -00000000  00 01 61 62                                       |..ab|",
+            "  +00000000  00 01 61 63                                       |..ac|",
+        ],
+    ],
+    approximate_checks_print_the_difference_and_the_tolerance: approximate_checks => [
+        [
+            "  check_approx_eq!( 0.1 + 0.2, 0.30001, abs = 1e-9 )",
+            "with expansion:",
+            "  0.30000000000000004 ≈ 0.30001",
+            "  difference = 9.99999999995449e-6 (180143985094 ULPs)",
+            "  tolerance = abs = 1e-9",
+            "with message:",
+            "  not close",
+        ],
+        [
+            "  check_approx_eq!( f32::NAN, 1.0 )",
+            "with expansion:",
+            "  NaN ≈ 1.0",
+            "Note: NaN is not approximately equal to anything",
+        ],
+    ],
+    absolute_difference_checks_print_the_values: absolute_difference_checks => [
+        [
+            "  check!( (measured - expected).abs() < 1e-9 )",
+            "with:",
+            "  measured = 0.30000000000000004",
+            "  expected = 0.30001",
+            "with expansion:",
+            "  9.99999999995449e-6 < 1e-9",
+            "with message:",
+            "  not close",
+        ],
+        [
+            "  check!( 1e-9 >= (measured - expected).abs() )",
+            "with:",
+            "  measured = 0.30000000000000004",
+            "  expected = 0.30001",
+            "with expansion:",
+            "  1e-9 >= 9.99999999995449e-6",
+        ],
+    ],
+    if_panic_idioms_print_the_negated_condition_and_the_panic_message: if_panic_checks => [
+        [
+            "  check!( left == right )",
+            "with expansion:",
+            "  1 == 2",
+            "with message:",
+            "  mismatch: 1 vs 2",
+        ],
+        [
+            "  check!( vec! [1, 2, 3].len() <= 2 )",
+            "with:",
+            "  vec! [1, 2, 3] = [1, 2, 3]",
+            "with expansion:",
+            "  3 <= 2",
+            "with message:",
+            "  too many values",
+        ],
+    ],
+    diverging_pattern_idioms_print_the_value_that_did_not_match: diverging_pattern_checks => [
+        [
+            "  let_assert!( let Ok(port) = error() )",
+            "with expansion:",
+            "  Err(\"bla\")",
+        ],
+    ],
+    negative_pattern_checks_print_the_matched_value: negative_pattern_checks => [
+        [
+            "  check!( !matches!(shape, Shape :: Point | Shape :: Square(_)) )",
+            "with expansion:",
+            "  Square(4)",
+            "Note: the value matches the pattern, but it must not",
+            "with message:",
+            "  not a line",
+        ],
+        [
+            "  check!( !matches!(shape, Shape :: Square(side) if side > 3) )",
+            "with:",
+            "  side = 4",
+            "Note: the value matches the pattern, but it must not",
+        ],
+    ],
+}